@@ -0,0 +1,45 @@
+/*
+ * C interface to the Advent of Code 2022 solvers, built as
+ * libadvent_of_code_2022.so by `cargo build`. Mirrors src/ffi.rs.
+ */
+#ifndef ADVENT_OF_CODE_2022_H
+#define ADVENT_OF_CODE_2022_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum {
+    AOC_KIND_ERROR = 0,    /* text holds the error message */
+    AOC_KIND_UNSIGNED = 1, /* unsigned_value holds the answer */
+    AOC_KIND_SIGNED = 2,   /* signed_value holds the answer */
+    AOC_KIND_TEXT = 3,     /* text only, including 128-bit answers too big for 64 bits */
+    AOC_KIND_GRID = 4,     /* text holds the rows joined by '\n' */
+} aoc_kind;
+
+typedef struct {
+    aoc_kind kind;
+    uint64_t unsigned_value;
+    int64_t signed_value;
+    /* the answer as text for every kind, owned by the result */
+    char *text;
+} aoc_result;
+
+/*
+ * Solve `part` ('a' or 'b') of `day` for the `len` bytes at `input`, which
+ * need not be NUL terminated. Never returns NULL, and solver failures come
+ * back as AOC_KIND_ERROR. Release the result with aoc_result_free.
+ */
+aoc_result *aoc_solve(uint32_t day, char part, const char *input, size_t len);
+
+/* Release a result from aoc_solve, NULL is ignored. */
+void aoc_result_free(aoc_result *result);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,274 @@
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, queue};
+use std::collections::HashMap;
+use std::io::{stdout, Write};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+// simulations push frames with the `animate!` macro. Like `explain!`, frames
+// are only built when animation is on, so it costs nothing otherwise
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ANIMATOR: Mutex<Option<Animator>> = Mutex::new(None);
+static RESTORE_ON_PANIC: Once = Once::new();
+
+pub const DEFAULT_FPS: u32 = 10;
+const MAX_FPS: u32 = 120;
+
+// lines reserved for the caption and the key help
+const RESERVED_ROWS: u16 = 2;
+const KEY_HELP: &str = "space pause | n step | arrows/hjkl scroll | +/- speed | q skip";
+
+pub struct Frame {
+    caption: String,
+    cells: HashMap<(i64, i64), char>,
+    focus: Option<(i64, i64)>,
+}
+
+impl Frame {
+    pub fn new(caption: impl Into<String>) -> Frame {
+        Frame {
+            caption: caption.into(),
+            cells: HashMap::new(),
+            focus: None,
+        }
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, c: char) {
+        // y increases downwards, as on the terminal
+        self.cells.insert((x, y), c);
+    }
+
+    pub fn text(&mut self, x: i64, y: i64, text: &str) {
+        for (idx, c) in text.chars().enumerate() {
+            self.set(x + idx as i64, y, c);
+        }
+    }
+
+    pub fn focus(&mut self, x: i64, y: i64) {
+        // the viewport follows the focus point when it would go off screen
+        self.focus = Some((x, y));
+    }
+
+    fn bounds(&self) -> ((i64, i64), (i64, i64)) {
+        let xs = self.cells.keys().map(|c| c.0);
+        let ys = self.cells.keys().map(|c| c.1);
+        (
+            (xs.clone().min().unwrap_or(0), ys.clone().min().unwrap_or(0)),
+            (xs.max().unwrap_or(0), ys.max().unwrap_or(0)),
+        )
+    }
+
+    fn lines(&self, origin: (i64, i64), width: u16, height: u16) -> Vec<String> {
+        (origin.1..origin.1 + height as i64)
+            .map(|y| {
+                (origin.0..origin.0 + width as i64)
+                    .map(|x| *self.cells.get(&(x, y)).unwrap_or(&' '))
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+}
+
+fn viewport_origin(
+    frame: &Frame,
+    previous: Option<(i64, i64)>,
+    width: u16,
+    height: u16,
+) -> (i64, i64) {
+    let (width, height) = (width as i64, height as i64);
+    match (frame.focus, previous) {
+        (Some((x, y)), Some((ox, oy)))
+            if x >= ox && x < ox + width && y >= oy && y < oy + height =>
+        {
+            (ox, oy)
+        }
+        // recentre on the focus point once it leaves the viewport
+        (Some((x, y)), _) => (x - width / 2, y - height / 2),
+        (None, Some(origin)) => origin,
+        (None, None) => frame.bounds().0,
+    }
+}
+
+struct Animator {
+    frame_interval: Duration,
+    paused: bool,
+    skipped: bool,
+    origin: Option<(i64, i64)>,
+    pan: (i64, i64),
+    frame_count: usize,
+}
+
+impl Animator {
+    fn draw(&mut self, frame: &Frame) {
+        let (cols, rows) = terminal::size().unwrap_or((80, 24));
+        let height = rows.saturating_sub(RESERVED_ROWS);
+        let origin = viewport_origin(frame, self.origin, cols, height);
+        self.origin = Some(origin);
+        let panned = (origin.0 + self.pan.0, origin.1 + self.pan.1);
+
+        let status = format!(
+            "frame {} | {} fps{} | {}",
+            self.frame_count,
+            1000 / self.frame_interval.as_millis().max(1),
+            if self.paused { " | paused" } else { "" },
+            KEY_HELP
+        );
+
+        let mut out = stdout();
+        queue!(out, cursor::MoveTo(0, 0), Clear(ClearType::All)).unwrap();
+        queue!(out, Print(&frame.caption), Print("\r\n")).unwrap();
+        for line in frame.lines(panned, cols, height) {
+            queue!(out, Print(line), Print("\r\n")).unwrap();
+        }
+        queue!(out, Print(status)).unwrap();
+        out.flush().unwrap();
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        // returns true when the animation should move on to the next frame
+        match key.code {
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            KeyCode::Char('n') | KeyCode::Enter => return true,
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.skipped = true;
+                return true;
+            }
+            KeyCode::Char('+') => {
+                self.frame_interval = (self.frame_interval / 2).max(min_interval())
+            }
+            KeyCode::Char('-') => self.frame_interval *= 2,
+            KeyCode::Left | KeyCode::Char('h') => self.pan.0 -= 1,
+            KeyCode::Right | KeyCode::Char('l') => self.pan.0 += 1,
+            KeyCode::Up | KeyCode::Char('k') => self.pan.1 -= 1,
+            KeyCode::Down | KeyCode::Char('j') => self.pan.1 += 1,
+            _ => (),
+        }
+        false
+    }
+
+    fn show(&mut self, frame: &Frame) {
+        self.frame_count += 1;
+        self.draw(frame);
+
+        let deadline = Instant::now() + self.frame_interval;
+        loop {
+            let timeout = if self.paused {
+                Duration::from_millis(100)
+            } else {
+                deadline.saturating_duration_since(Instant::now())
+            };
+            if event::poll(timeout).unwrap() {
+                if let Event::Key(key) = event::read().unwrap() {
+                    if key.kind != KeyEventKind::Release && self.handle_key(key) {
+                        return;
+                    }
+                    // redraw to show pans, pauses and speed changes
+                    self.draw(frame);
+                }
+            } else if !self.paused {
+                return;
+            }
+        }
+    }
+}
+
+fn min_interval() -> Duration {
+    Duration::from_millis(1000 / MAX_FPS as u64)
+}
+
+fn restore_terminal() {
+    let _ = terminal::disable_raw_mode();
+    let _ = queue!(stdout(), LeaveAlternateScreen, cursor::Show);
+    let _ = stdout().flush();
+}
+
+pub fn start(fps: u32) {
+    terminal::enable_raw_mode().expect("--animate needs an interactive terminal");
+    queue!(stdout(), EnterAlternateScreen, cursor::Hide).unwrap();
+
+    // make sure a panicking solver doesn't leave the terminal in raw mode.
+    // The hook stays for later parts, so it's only installed once
+    RESTORE_ON_PANIC.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if ENABLED.load(Ordering::Relaxed) {
+                restore_terminal();
+            }
+            default_hook(info);
+        }));
+    });
+
+    *ANIMATOR.lock().unwrap() = Some(Animator {
+        frame_interval: Duration::from_millis(1000 / fps.clamp(1, MAX_FPS) as u64),
+        paused: false,
+        skipped: false,
+        origin: None,
+        pan: (0, 0),
+        frame_count: 0,
+    });
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn finish() {
+    if ENABLED.swap(false, Ordering::Relaxed) {
+        *ANIMATOR.lock().unwrap() = None;
+        restore_terminal();
+    }
+}
+
+#[inline]
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn push(frame: Frame) {
+    if let Some(animator) = ANIMATOR.lock().unwrap().as_mut() {
+        if !animator.skipped {
+            animator.show(&frame);
+        }
+    }
+}
+
+/// Push a frame to the terminal animation when running with `--animate`.
+/// The frame expression is only evaluated when animation is on.
+#[macro_export]
+macro_rules! animate {
+    ($frame:expr) => {
+        if $crate::animation::enabled() {
+            $crate::animation::push($frame);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_lines() {
+        let mut frame = Frame::new("test");
+        frame.text(0, 0, "ab");
+        frame.set(1, 1, 'c');
+        assert_eq!(frame.lines((0, 0), 3, 2), vec!["ab", " c"]);
+        assert_eq!(frame.lines((1, 0), 1, 2), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_viewport_follows_focus() {
+        let mut frame = Frame::new("test");
+        frame.set(-5, -5, '#');
+        assert_eq!(viewport_origin(&frame, None, 10, 10), (-5, -5));
+
+        frame.focus(2, 2);
+        assert_eq!(viewport_origin(&frame, Some((0, 0)), 10, 10), (0, 0));
+
+        frame.focus(20, 2);
+        assert_eq!(viewport_origin(&frame, Some((0, 0)), 10, 10), (15, -3));
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// expected answers live next to the inputs they belong to, so every input
+// set keeps its own answers. One answer per line: `<day> <part> <answer>`
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+pub struct Answers {
+    path: PathBuf,
+    entries: BTreeMap<(u32, char), String>,
+}
+
+fn escape(answer: &str) -> String {
+    // multi-line answers (e.g. day 10's CRT screen) are stored on one line
+    answer.replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    answer.replace("\\n", "\n")
+}
+
+fn parse_line(line: &str) -> Option<((u32, char), String)> {
+    let mut fields = line.splitn(3, ' ');
+    let day = fields.next()?.parse::<u32>().ok()?;
+    let part = fields.next()?.chars().next()?;
+    let answer = unescape(fields.next()?);
+    Some(((day, part), answer))
+}
+
+impl Answers {
+    pub fn load(dir: &Path) -> Answers {
+        let path = dir.join(ANSWERS_FILE);
+        let entries = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(parse_line)
+            .collect();
+        Answers { path, entries }
+    }
+
+    pub fn get(&self, day: u32, part: char) -> Option<&String> {
+        self.entries.get(&(day, part))
+    }
+
+    pub fn set(&mut self, day: u32, part: char, answer: &str) {
+        self.entries.insert((day, part), answer.to_string());
+    }
+
+    pub fn save(&self) {
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|((day, part), answer)| format!("{} {} {}\n", day, part, escape(answer)))
+            .collect();
+        fs::write(&self.path, contents)
+            .unwrap_or_else(|_| panic!("could not write {}", self.path.display()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(parse_line("1 a 101"), Some(((1, 'a'), "101".to_string())));
+        assert_eq!(
+            parse_line("10 b ##..\\n.##."),
+            Some(((10, 'b'), "##..\n.##.".to_string()))
+        );
+        assert_eq!(parse_line("not an answer"), None);
+    }
+
+    #[test]
+    fn test_escape_round_trip() {
+        let answer = "\n##..\n.##.";
+        assert_eq!(unescape(&escape(answer)), answer);
+    }
+}
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// solvers do the arithmetic that can overflow on bigger inputs through these
+// helpers. Normally they are the plain operators, with `--checked` every
+// operation is checked and an overflow is returned as an `Overflow` naming the
+// day and the operation instead of wrapping or a bare panic
+static CHECKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Overflow {
+    pub day: u32,
+    pub operation: String,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {} overflowed computing {}",
+            self.day, self.operation
+        )
+    }
+}
+
+pub fn set_checked(checked: bool) {
+    CHECKED.store(checked, Ordering::Relaxed);
+}
+
+#[inline]
+pub fn checked() -> bool {
+    CHECKED.load(Ordering::Relaxed)
+}
+
+fn overflow<T>(day: u32, operation: String) -> Result<T, Overflow> {
+    Err(Overflow { day, operation })
+}
+
+pub trait Integer:
+    Copy + fmt::Display + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),+) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+        })+
+    };
+}
+
+impl_integer!(u32, u64, i64, usize);
+
+// `what` names the value being computed, e.g. "space to clear"
+pub fn add<T: Integer>(day: u32, what: &str, a: T, b: T) -> Result<T, Overflow> {
+    add_with(checked(), day, what, a, b)
+}
+
+pub fn sub<T: Integer>(day: u32, what: &str, a: T, b: T) -> Result<T, Overflow> {
+    sub_with(checked(), day, what, a, b)
+}
+
+pub fn mul<T: Integer>(day: u32, what: &str, a: T, b: T) -> Result<T, Overflow> {
+    mul_with(checked(), day, what, a, b)
+}
+
+pub fn sum<T: Integer>(
+    day: u32,
+    what: &str,
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, Overflow> {
+    sum_with(checked(), day, what, values)
+}
+
+pub fn product<T: Integer>(
+    day: u32,
+    what: &str,
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, Overflow> {
+    product_with(checked(), day, what, values)
+}
+
+// the helpers above with the mode passed in rather than read from `CHECKED`,
+// so tests can check overflows without flipping it under other tests
+fn add_with<T: Integer>(checked: bool, day: u32, what: &str, a: T, b: T) -> Result<T, Overflow> {
+    if !checked {
+        return Ok(a + b);
+    }
+    match a.checked_add(b) {
+        Some(value) => Ok(value),
+        None => overflow(day, format!("{} = {} + {}", what, a, b)),
+    }
+}
+
+fn sub_with<T: Integer>(checked: bool, day: u32, what: &str, a: T, b: T) -> Result<T, Overflow> {
+    if !checked {
+        return Ok(a - b);
+    }
+    match a.checked_sub(b) {
+        Some(value) => Ok(value),
+        None => overflow(day, format!("{} = {} - {}", what, a, b)),
+    }
+}
+
+fn mul_with<T: Integer>(checked: bool, day: u32, what: &str, a: T, b: T) -> Result<T, Overflow> {
+    if !checked {
+        return Ok(a * b);
+    }
+    match a.checked_mul(b) {
+        Some(value) => Ok(value),
+        None => overflow(day, format!("{} = {} * {}", what, a, b)),
+    }
+}
+
+fn sum_with<T: Integer>(
+    checked: bool,
+    day: u32,
+    what: &str,
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, Overflow> {
+    values.into_iter().try_fold(T::ZERO, |total, value| {
+        add_with(checked, day, what, total, value)
+    })
+}
+fn product_with<T: Integer>(
+    checked: bool,
+    day: u32,
+    what: &str,
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, Overflow> {
+    values.into_iter().try_fold(T::ONE, |total, value| {
+        mul_with(checked, day, what, total, value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_range() {
+        assert_eq!(add(1, "total", 2u32, 3), Ok(5));
+        assert_eq!(sub(7, "space", 5u64, 3), Ok(2));
+        assert_eq!(sum(1, "total", [1u32, 2, 3]), Ok(6));
+        assert_eq!(product(11, "monkey business", [2u64, 3, 4]), Ok(24));
+        assert_eq!(add_with(true, 1, "total", 2u32, 3), Ok(5));
+    }
+
+    #[test]
+    fn test_overflow_names_day_and_operation() {
+        assert_eq!(
+            sub_with(true, 7, "space to clear", 1u64, 2),
+            Err(Overflow {
+                day: 7,
+                operation: "space to clear = 1 - 2".to_string()
+            })
+        );
+        assert_eq!(
+            sum_with(true, 1, "calories", [u32::MAX, 1])
+                .unwrap_err()
+                .to_string(),
+            "day 1 overflowed computing calories = 4294967295 + 1"
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// whether the latest `--check` of each part matched its expected answer,
+// kept next to the answers of the set it was checked against. Verdicts
+// depend on the local code, so the file is kept out of the repo. One
+// verdict per line: `<day> <part> <correct|incorrect>`
+pub const CHECKS_FILE: &str = ".aoc_checks";
+
+const CORRECT: &str = "correct";
+const INCORRECT: &str = "incorrect";
+
+pub struct Checks {
+    path: PathBuf,
+    entries: BTreeMap<(u32, char), bool>,
+}
+
+fn parse_line(line: &str) -> Option<((u32, char), bool)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let day = fields.first()?.parse::<u32>().ok()?;
+    let part = fields.get(1)?.chars().next()?;
+    let correct = match *fields.get(2)? {
+        CORRECT => true,
+        INCORRECT => false,
+        _ => return None,
+    };
+    Some(((day, part), correct))
+}
+
+impl Checks {
+    pub fn load(dir: &Path) -> Checks {
+        let path = dir.join(CHECKS_FILE);
+        let entries = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(parse_line)
+            .collect();
+        Checks { path, entries }
+    }
+
+    pub fn get(&self, day: u32, part: char) -> Option<bool> {
+        self.entries.get(&(day, part)).copied()
+    }
+
+    pub fn record(&mut self, day: u32, part: char, correct: bool) {
+        self.entries.insert((day, part), correct);
+    }
+
+    pub fn forget(&mut self, day: u32, part: char) {
+        // e.g. once a new answer is saved, until it is checked against
+        self.entries.remove(&(day, part));
+    }
+
+    pub fn save(&self) {
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|((day, part), correct)| {
+                let verdict = if *correct { CORRECT } else { INCORRECT };
+                format!("{} {} {}\n", day, part, verdict)
+            })
+            .collect();
+        fs::write(&self.path, contents)
+            .unwrap_or_else(|_| panic!("could not write {}", self.path.display()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(parse_line("1 a correct"), Some(((1, 'a'), true)));
+        assert_eq!(parse_line("10 b incorrect"), Some(((10, 'b'), false)));
+        assert_eq!(parse_line("10 b maybe"), None);
+    }
+}
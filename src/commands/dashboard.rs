@@ -0,0 +1,566 @@
+use super::{parse_subcommand_args, star, MISSING, VERIFIED};
+use advent_of_code_2022::answers::Answers;
+use advent_of_code_2022::checks::Checks;
+use advent_of_code_2022::days::{self, Options, Solution, Solver};
+use advent_of_code_2022::render::Image;
+use advent_of_code_2022::runtimes::{format_runtime, Runtimes};
+use advent_of_code_2022::{input_exists, read_year_file, set_dir, trace, ReturnType};
+use advent_of_code_2022::{DAYS, DEFAULT_YEAR, PARTS, TEST_SET};
+use argparse::{ArgumentParser, Store};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute};
+use crossterm::style::{SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, queue};
+use std::collections::HashMap;
+use std::env;
+use std::io::{stdout, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// the real inputs, then the test inputs
+const SETS: [Option<&str>; 2] = [None, Some(TEST_SET)];
+
+const ANSWER_WIDTH: usize = 18;
+
+// the title and column headings above the calendar, the message and key help below
+const HEADER_ROWS: u16 = 2;
+const FOOTER_ROWS: u16 = 2;
+const CALENDAR_HELP: &str =
+    "up/down select | enter run | t real/test inputs | e trace | v render | q quit";
+const VIEW_HELP: &str = "up/down scroll | esc back";
+
+enum View {
+    Calendar,
+    Trace,
+    Render,
+}
+
+struct PartRun {
+    answer: Result<ReturnType, String>,
+    runtime: Duration,
+}
+
+// the latest results for one day against one input set
+#[derive(Default)]
+struct DayRun {
+    parts: HashMap<char, PartRun>,
+    trace: Option<Vec<String>>,
+    render: Option<Image>,
+}
+
+// set while a solver runs, so the panic hook leaves its panics to `quietly`
+static SOLVING: AtomicBool = AtomicBool::new(false);
+
+fn quietly<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    // a panicking solver shouldn't take the dashboard down or print over it
+    SOLVING.store(true, Ordering::Relaxed);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    SOLVING.store(false, Ordering::Relaxed);
+    result.map_err(|payload| days::panic_message(payload.as_ref()))
+}
+
+fn answer_cell(answer: &Result<ReturnType, String>, expected: Option<&String>) -> String {
+    let (text, verdict) = match answer {
+        Ok(answer) => {
+            // grids are squashed onto one line, the render view shows them properly
+            let text = answer.to_string().trim().replace('\n', " ");
+            let verdict = match expected {
+                Some(expected) if answer.matches(expected) => " ok",
+                Some(_) => " wrong",
+                None => "",
+            };
+            (text, verdict)
+        }
+        Err(_) => ("error".to_string(), ""),
+    };
+    let room = ANSWER_WIDTH - verdict.len();
+    if text.chars().count() > room {
+        let shortened: String = text.chars().take(room - 1).collect();
+        format!("{}~{}", shortened, verdict)
+    } else {
+        format!("{}{}", text, verdict)
+    }
+}
+
+fn fit_scale(width: usize, height: usize, cols: u16, rows: u16) -> usize {
+    // each terminal cell shows two pixels stacked, using a half block
+    let cols = (cols as usize).max(1);
+    let rows = (rows as usize * 2).max(1);
+    width.div_ceil(cols).max(height.div_ceil(rows)).max(1)
+}
+
+fn solvers(solution: &Solution) -> Vec<(char, Solver)> {
+    PARTS
+        .iter()
+        .filter_map(|p| solution.part(*p).map(|solver| (*p, solver)))
+        .collect()
+}
+
+struct Dashboard {
+    year: u32,
+    solutions: Vec<Solution>,
+    expected: Vec<Answers>,
+    checks: Vec<Checks>,
+    runtimes: Runtimes,
+    runs: HashMap<(usize, u32), DayRun>,
+    set: usize,
+    selected: u32,
+    view: View,
+    scroll: usize,
+    message: String,
+}
+
+impl Dashboard {
+    fn new(year: u32) -> Dashboard {
+        Dashboard {
+            year,
+            solutions: days::registry()
+                .into_iter()
+                .filter(|s| s.year == year)
+                .collect(),
+            expected: SETS
+                .iter()
+                .map(|s| Answers::load(&set_dir(year, *s)))
+                .collect(),
+            checks: SETS
+                .iter()
+                .map(|s| Checks::load(&set_dir(year, *s)))
+                .collect(),
+            runtimes: Runtimes::load(&env::current_dir().unwrap()),
+            runs: HashMap::new(),
+            set: 0,
+            selected: 1,
+            view: View::Calendar,
+            scroll: 0,
+            message: String::new(),
+        }
+    }
+
+    fn solution(&self, day: u32) -> Option<&Solution> {
+        self.solutions.iter().find(|s| s.day == day)
+    }
+
+    fn star(&self, day: u32, part: char) -> &'static str {
+        let solver = self.solution(day).and_then(|s| s.part(part));
+        star(solver, self.checks[self.set].get(day, part))
+    }
+
+    fn set_name(&self) -> &str {
+        SETS[self.set].unwrap_or("real")
+    }
+
+    fn input(&mut self) -> Option<String> {
+        // the selected day's input in the current set, or why there isn't one
+        let day = self.selected;
+        if self.solution(day).is_none() {
+            self.message = format!("day {} is not implemented", day);
+            return None;
+        }
+        if !input_exists(self.year, day, SETS[self.set]) {
+            self.message = format!("day {} has no {} input", day, self.set_name());
+            return None;
+        }
+        quietly(|| read_year_file(self.year, day, SETS[self.set]))
+            .map_err(|e| self.message = e)
+            .ok()
+    }
+
+    fn run(&mut self) {
+        let Some(input) = self.input() else {
+            return;
+        };
+        let (day, set) = (self.selected, SETS[self.set]);
+        let solution = self.solution(day).unwrap();
+        let (parse, solvers) = (solution.parse, solvers(solution));
+        let parsed = match quietly(|| parse(&input)) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.message = format!("day {} failed to parse: {}", day, e);
+                return;
+            }
+        };
+        let mut parts = HashMap::new();
+        for (part, solver) in solvers {
+            let start = Instant::now();
+            let answer = quietly(|| solver.solve(parsed.as_ref(), Options::default()))
+                .and_then(|answer| answer.map_err(|error| error.to_string()));
+            let runtime = start.elapsed();
+            if set.is_none() && answer.is_ok() {
+                // as with the CLI, status keeps the latest real runtimes
+                self.runtimes.record(self.year, day, part, runtime);
+            }
+            // running against a saved answer checks it, like `--check`
+            if let (Ok(answer), Some(expected)) = (&answer, self.expected[self.set].get(day, part))
+            {
+                let correct = answer.matches(expected);
+                self.checks[self.set].record(day, part, correct);
+            }
+            parts.insert(part, PartRun { answer, runtime });
+        }
+        self.runtimes.save();
+        self.checks[self.set].save();
+
+        let errors: Vec<String> = parts
+            .iter()
+            .filter_map(|(part, run)| {
+                run.answer
+                    .as_ref()
+                    .err()
+                    .map(|e| format!("{}: {}", part, e))
+            })
+            .collect();
+        self.message = if errors.is_empty() {
+            format!("ran day {} against the {} input", day, self.set_name())
+        } else {
+            format!("day {} failed, {}", day, errors.join(", "))
+        };
+        self.runs.entry((self.set, day)).or_default().parts = parts;
+    }
+
+    fn trace(&mut self) {
+        // traces slow solvers down, so they're only captured when asked for
+        let key = (self.set, self.selected);
+        if self.runs.get(&key).is_some_and(|r| r.trace.is_some()) {
+            return;
+        }
+        let Some(input) = self.input() else {
+            return;
+        };
+        let solution = self.solution(self.selected).unwrap();
+        trace::set_explain(1, false);
+        trace::start_capture();
+        let result = quietly(|| {
+            let parsed = (solution.parse)(&input);
+            solvers(solution).into_iter().try_for_each(|(_, solver)| {
+                solver
+                    .solve(parsed.as_ref(), Options::default())
+                    .map(|_| ())
+            })
+        })
+        .and_then(|solved| solved.map_err(|error| error.to_string()));
+        let mut lines = trace::finish_capture();
+        trace::set_explain(0, false);
+        if let Err(e) = result {
+            lines.push(format!("failed: {}", e));
+        }
+        if lines.is_empty() {
+            lines.push(format!("day {} doesn't explain its steps", self.selected));
+        }
+        self.runs.entry(key).or_default().trace = Some(lines);
+    }
+
+    fn render(&mut self) -> bool {
+        let key = (self.set, self.selected);
+        if self.runs.get(&key).is_some_and(|r| r.render.is_some()) {
+            return true;
+        }
+        let renderer = self.solution(self.selected).and_then(|s| s.render);
+        let Some(renderer) = renderer else {
+            self.message = format!("day {} has no render", self.selected);
+            return false;
+        };
+        let Some(input) = self.input() else {
+            return false;
+        };
+        match quietly(|| renderer(&input)) {
+            Ok(image) => {
+                self.runs.entry(key).or_default().render = Some(image);
+                true
+            }
+            Err(e) => {
+                self.message = format!("day {} failed to render: {}", self.selected, e);
+                false
+            }
+        }
+    }
+
+    fn calendar_row(&self, day: u32) -> String {
+        let solution = self.solution(day);
+        let expected = &self.expected[self.set];
+        let run = self.runs.get(&(self.set, day));
+        let stars: Vec<&str> = PARTS.iter().map(|p| self.star(day, *p)).collect();
+        let cells: Vec<(String, String)> = PARTS
+            .iter()
+            .map(|p| match run.and_then(|r| r.parts.get(p)) {
+                Some(part) => (
+                    answer_cell(&part.answer, expected.get(day, *p)),
+                    format_runtime(&part.runtime),
+                ),
+                // the latest real runtime is remembered between sessions
+                None => (
+                    MISSING.to_string(),
+                    SETS[self.set]
+                        .is_none()
+                        .then(|| self.runtimes.get(self.year, day, *p))
+                        .flatten()
+                        .map_or(MISSING.to_string(), format_runtime),
+                ),
+            })
+            .collect();
+        format!(
+            "{:>3}  {:<6}  {:<2} {:<2}  {:<5}  {:<w$}  {:<w$}  {:>10}  {:>10}",
+            day,
+            solution.map_or("no", |s| if s.is_plugin() { "plugin" } else { "yes" }),
+            stars[0],
+            stars[1],
+            if input_exists(self.year, day, SETS[self.set]) {
+                "yes"
+            } else {
+                "no"
+            },
+            cells[0].0,
+            cells[1].0,
+            cells[0].1,
+            cells[1].1,
+            w = ANSWER_WIDTH
+        )
+    }
+
+    fn draw(&self) {
+        let (cols, rows) = terminal::size().unwrap_or((80, 24));
+        let body = rows.saturating_sub(HEADER_ROWS + FOOTER_ROWS) as usize;
+        let mut out = stdout();
+        queue!(out, cursor::MoveTo(0, 0), Clear(ClearType::All)).unwrap();
+
+        let help = match self.view {
+            View::Calendar => {
+                let stars = DAYS
+                    .flat_map(|day| PARTS.map(|p| self.star(day, p)))
+                    .filter(|s| *s == VERIFIED)
+                    .count();
+                let title = format!(
+                    "Advent of Code {} | {} inputs | stars {}/{}",
+                    self.year,
+                    self.set_name(),
+                    stars,
+                    DAYS.count() * PARTS.len()
+                );
+                let headings = format!(
+                    "{:>3}  {:<6}  {:<2} {:<2}  {:<5}  {:<w$}  {:<w$}  {:>10}  {:>10}",
+                    "Day",
+                    "Solver",
+                    "a",
+                    "b",
+                    "Input",
+                    "Answer a",
+                    "Answer b",
+                    "Runtime a",
+                    "Runtime b",
+                    w = ANSWER_WIDTH
+                );
+                queue!(out, Print(title), Print("\r\n"), Print(headings)).unwrap();
+                // keep the selected day in view on short terminals
+                let first = (self.selected as usize).saturating_sub(body.max(1));
+                for day in DAYS.skip(first).take(body) {
+                    queue!(out, Print("\r\n")).unwrap();
+                    if day == self.selected {
+                        queue!(out, SetAttribute(Attribute::Reverse)).unwrap();
+                    }
+                    queue!(
+                        out,
+                        Print(self.calendar_row(day)),
+                        SetAttribute(Attribute::Reset)
+                    )
+                    .unwrap();
+                }
+                CALENDAR_HELP
+            }
+            View::Trace => {
+                let lines = self
+                    .runs
+                    .get(&(self.set, self.selected))
+                    .and_then(|r| r.trace.as_ref());
+                let title = format!("Day {} trace, {} input", self.selected, self.set_name());
+                queue!(out, Print(title), Print("\r\n")).unwrap();
+                for line in lines.into_iter().flatten().skip(self.scroll).take(body + 1) {
+                    let line: String = line.chars().take(cols as usize).collect();
+                    queue!(out, Print("\r\n"), Print(line)).unwrap();
+                }
+                VIEW_HELP
+            }
+            View::Render => {
+                let title = format!("Day {} render, {} input", self.selected, self.set_name());
+                queue!(out, Print(title), Print("\r\n")).unwrap();
+                if let Some(image) = self
+                    .runs
+                    .get(&(self.set, self.selected))
+                    .and_then(|r| r.render.as_ref())
+                {
+                    draw_image(image, cols, body as u16 + 1, self.scroll);
+                }
+                VIEW_HELP
+            }
+        };
+
+        // e.g. day 10's OCR failure goes on to draw the unreadable picture
+        let message: String = self
+            .message
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(cols as usize)
+            .collect();
+        queue!(
+            out,
+            cursor::MoveTo(0, rows.saturating_sub(FOOTER_ROWS)),
+            Print(message),
+            cursor::MoveTo(0, rows.saturating_sub(1)),
+            Print(help)
+        )
+        .unwrap();
+        out.flush().unwrap();
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        // returns false when the dashboard should close
+        match (&self.view, code) {
+            (View::Calendar, KeyCode::Char('q') | KeyCode::Esc) => return false,
+            (View::Calendar, KeyCode::Up | KeyCode::Char('k')) => {
+                self.selected = (self.selected - 1).max(*DAYS.start())
+            }
+            (View::Calendar, KeyCode::Down | KeyCode::Char('j')) => {
+                self.selected = (self.selected + 1).min(*DAYS.end())
+            }
+            (View::Calendar, KeyCode::Enter | KeyCode::Char('r')) => {
+                self.message = format!("running day {}...", self.selected);
+                self.draw();
+                self.run();
+            }
+            (View::Calendar, KeyCode::Char('t')) => {
+                self.set = (self.set + 1) % SETS.len();
+                self.message = format!("switched to the {} inputs", self.set_name());
+            }
+            (View::Calendar, KeyCode::Char('e')) => {
+                self.message = format!("tracing day {}...", self.selected);
+                self.draw();
+                self.message.clear();
+                self.trace();
+                self.scroll = 0;
+                self.view = View::Trace;
+            }
+            (View::Calendar, KeyCode::Char('v')) => {
+                self.message.clear();
+                if self.render() {
+                    self.scroll = 0;
+                    self.view = View::Render;
+                }
+            }
+            (_, KeyCode::Char('q') | KeyCode::Esc) => self.view = View::Calendar,
+            (_, KeyCode::Up | KeyCode::Char('k')) => self.scroll = self.scroll.saturating_sub(1),
+            (_, KeyCode::Down | KeyCode::Char('j')) => self.scroll += 1,
+            (_, KeyCode::PageUp) => self.scroll = self.scroll.saturating_sub(20),
+            (_, KeyCode::PageDown) => self.scroll += 20,
+            _ => (),
+        }
+        true
+    }
+}
+
+fn draw_image(image: &Image, cols: u16, rows: u16, scroll: usize) {
+    // scrolling zooms in, one step at a time down to a pixel per half cell
+    let scale = fit_scale(image.width, image.height, cols, rows)
+        .saturating_sub(scroll)
+        .max(1);
+    let mut out = stdout();
+    let pixel = |x: usize, y: usize| {
+        let (x, y) = (x * scale, y * scale);
+        if x < image.width && y < image.height {
+            let c = image.get(x, y);
+            Color::Rgb {
+                r: c.0,
+                g: c.1,
+                b: c.2,
+            }
+        } else {
+            Color::Reset
+        }
+    };
+    let width = image.width.div_ceil(scale).min(cols as usize);
+    let height = image.height.div_ceil(scale * 2).min(rows as usize);
+    for y in 0..height {
+        queue!(out, Print("\r\n")).unwrap();
+        for x in 0..width {
+            queue!(
+                out,
+                SetForegroundColor(pixel(x, y * 2)),
+                SetBackgroundColor(pixel(x, y * 2 + 1)),
+                Print('▀')
+            )
+            .unwrap();
+        }
+        queue!(out, ResetColor).unwrap();
+    }
+}
+
+fn restore_terminal() {
+    let _ = terminal::disable_raw_mode();
+    let _ = queue!(stdout(), LeaveAlternateScreen, cursor::Show);
+    let _ = stdout().flush();
+}
+
+pub fn run(args: Vec<String>) {
+    let mut year = DEFAULT_YEAR;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("Browse, run and inspect every day in a full-screen dashboard.");
+        ap.refer(&mut year)
+            .add_option(&["-y", "--year"], Store, "Year to show, defaults to 2022");
+        parse_subcommand_args(ap, args);
+    }
+
+    days::load_plugins();
+    let mut dashboard = Dashboard::new(year);
+    terminal::enable_raw_mode().expect("the dashboard needs an interactive terminal");
+    queue!(stdout(), EnterAlternateScreen, cursor::Hide).unwrap();
+    // solver panics are caught and shown quietly, but the dashboard's own
+    // bugs should still leave a usable terminal
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !SOLVING.load(Ordering::Relaxed) {
+            restore_terminal();
+            default_hook(info);
+        }
+    }));
+
+    loop {
+        dashboard.draw();
+        if let Event::Key(key) = event::read().unwrap() {
+            if key.kind != KeyEventKind::Release && !dashboard.handle_key(key.code) {
+                break;
+            }
+        }
+    }
+    restore_terminal();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_cell() {
+        let expected = "24933642".to_string();
+        let answer = Ok(ReturnType::UInt64(24933642));
+        assert_eq!(answer_cell(&answer, Some(&expected)), "24933642 ok");
+        assert_eq!(answer_cell(&answer, None), "24933642");
+        assert_eq!(
+            answer_cell(&Ok(ReturnType::UInt64(1)), Some(&expected)),
+            "1 wrong"
+        );
+        assert_eq!(
+            answer_cell(&Ok(ReturnType::String("a".repeat(30))), None),
+            format!("{}~", "a".repeat(ANSWER_WIDTH - 1))
+        );
+        assert_eq!(answer_cell(&Err("boom".to_string()), None), "error");
+    }
+
+    #[test]
+    fn test_fit_scale() {
+        assert_eq!(fit_scale(10, 10, 80, 24), 1);
+        assert_eq!(fit_scale(160, 41, 80, 24), 2);
+        assert_eq!(fit_scale(99, 99, 80, 20), 3);
+    }
+}
@@ -0,0 +1,97 @@
+use super::parse_subcommand_args;
+use advent_of_code_2022::answers::Answers;
+use advent_of_code_2022::examples;
+use advent_of_code_2022::{set_dir, DAYS, DEFAULT_YEAR, PARTS, TEST_SET};
+use argparse::{ArgumentParser, Store, StoreTrue};
+use std::fs;
+
+fn import(year: u32, day: u32, page: &str, block: usize, force: bool) {
+    let html =
+        fs::read_to_string(page).unwrap_or_else(|e| panic!("could not read {}: {}", page, e));
+    let example = examples::extract(&html);
+    if example.blocks.is_empty() {
+        panic!("no <pre><code> example blocks found in {}", page);
+    }
+    let input = example.blocks.get(block - 1).unwrap_or_else(|| {
+        panic!(
+            "--block {} was given but {} only has {} example blocks",
+            block,
+            page,
+            example.blocks.len()
+        )
+    });
+
+    let dir = set_dir(year, Some(TEST_SET));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("day_{}.txt", day));
+    if path.exists() && !force {
+        println!("Skipped {} as it already exists", path.display());
+    } else {
+        fs::write(&path, input).unwrap();
+        println!("Wrote example input to {}", path.display());
+    }
+
+    let mut answers = Answers::load(&dir);
+    for (part, answer) in PARTS.iter().zip(&example.answers) {
+        if answers.get(day, *part).is_some() && !force {
+            println!("Skipped part {} as it already has an expected answer", part);
+            continue;
+        }
+        answers.set(day, *part, answer);
+        println!("Expected answer for part {}: {}", part, answer);
+    }
+    answers.save();
+    if example.answers.len() < PARTS.len() {
+        // part two's text is only on the page once part one is solved
+        println!("Save the page again after solving part a to import part b's answer");
+    }
+}
+
+pub fn run(args: Vec<String>) {
+    let mut action = String::new();
+    let mut page = String::new();
+    let mut year = DEFAULT_YEAR;
+    let mut day: u32 = 0;
+    let mut block: usize = 1;
+    let mut force = false;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("Import example inputs and answers from saved puzzle pages.");
+        ap.refer(&mut action)
+            .required()
+            .add_argument("action", Store, "Action to run [import]");
+        ap.refer(&mut page)
+            .required()
+            .add_argument("page", Store, "Puzzle page saved as HTML");
+        ap.refer(&mut year).add_option(
+            &["-y", "--year"],
+            Store,
+            "Year of the puzzle, defaults to 2022",
+        );
+        ap.refer(&mut day)
+            .required()
+            .add_option(&["-d", "--day"], Store, "Day of the puzzle");
+        ap.refer(&mut block).add_option(
+            &["--block"],
+            Store,
+            "Which example block to use as the input, defaults to the first",
+        );
+        ap.refer(&mut force).add_option(
+            &["-f", "--force"],
+            StoreTrue,
+            "Overwrite an existing test input and expected answers",
+        );
+        parse_subcommand_args(ap, args);
+    }
+
+    if !DAYS.contains(&day) {
+        panic!("--day parameter must be between `1` and `25` inclusive");
+    }
+    if block == 0 {
+        panic!("--block counts from 1");
+    }
+    match action.as_str() {
+        "import" => import(year, day, &page, block, force),
+        _ => panic!("Unrecognised examples action [{}]", action),
+    }
+}
@@ -0,0 +1,116 @@
+use super::parse_subcommand_args;
+use advent_of_code_2022::crypto;
+use advent_of_code_2022::{input_dir, DAYS, INPUTS_DIR, TEST_SET};
+use argparse::{ArgumentParser, Store, StoreTrue};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn input_dirs(dir: &Path) -> Vec<PathBuf> {
+    // the inputs directory and everything below it, i.e. the flat layout,
+    // year directories and named sets. Test inputs are committed as is
+    let mut dirs = vec![dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(dir) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.is_dir() && path.file_name().is_some_and(|n| n != TEST_SET) {
+                dirs.extend(input_dirs(&path));
+            }
+        }
+    }
+    dirs
+}
+
+fn plaintext_paths(day: u32) -> Vec<PathBuf> {
+    let days: Vec<u32> = if day == 0 { DAYS.collect() } else { vec![day] };
+    input_dirs(&input_dir(vec![INPUTS_DIR]))
+        .iter()
+        .flat_map(|dir| days.iter().map(|d| dir.join(format!("day_{}.txt", d))))
+        .collect()
+}
+
+fn load_key() -> Vec<u8> {
+    crypto::load_key(&crypto::key_path()).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn keygen(force: bool) {
+    let path = crypto::key_path();
+    if path.exists() && !force {
+        panic!(
+            "key file {} already exists, pass --force to replace it",
+            path.display()
+        );
+    }
+    crypto::write_key(&path, &crypto::generate_key())
+        .unwrap_or_else(|e| panic!("could not write {}: {}", path.display(), e));
+    println!("Wrote new key to {}", path.display());
+}
+
+fn is_encrypted(key: &[u8], plaintext: &[u8], encrypted_path: &Path) -> bool {
+    // each encryption uses a fresh nonce, so compare the decrypted contents
+    // rather than the files to avoid churning unchanged inputs
+    fs::read(encrypted_path)
+        .ok()
+        .and_then(|data| crypto::decrypt(key, &data).ok())
+        .is_some_and(|decrypted| decrypted == plaintext)
+}
+
+fn encrypt(day: u32, force: bool) {
+    let key = load_key();
+    for path in plaintext_paths(day).iter().filter(|p| p.exists()) {
+        let plaintext = fs::read(path).unwrap();
+        let encrypted_path = crypto::encrypted_path(path);
+        if !force && is_encrypted(&key, &plaintext, &encrypted_path) {
+            println!("Skipped {} as it is unchanged", encrypted_path.display());
+            continue;
+        }
+        fs::write(&encrypted_path, crypto::encrypt(&key, &plaintext)).unwrap();
+        println!("Encrypted {}", encrypted_path.display());
+    }
+}
+
+fn decrypt(day: u32, force: bool) {
+    let key = load_key();
+    for path in plaintext_paths(day) {
+        let encrypted_path = crypto::encrypted_path(&path);
+        if !encrypted_path.exists() {
+            continue;
+        }
+        if path.exists() && !force {
+            println!("Skipped {} as it already exists", path.display());
+            continue;
+        }
+        let decrypted = crypto::decrypt(&key, &fs::read(&encrypted_path).unwrap())
+            .unwrap_or_else(|e| panic!("could not decrypt {}: {}", encrypted_path.display(), e));
+        fs::write(&path, decrypted).unwrap();
+        println!("Decrypted {}", path.display());
+    }
+}
+
+pub fn run(args: Vec<String>) {
+    let mut action = String::new();
+    let mut day: u32 = 0;
+    let mut force = false;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("Manage encrypted puzzle inputs.");
+        ap.refer(&mut action).required().add_argument(
+            "action",
+            Store,
+            "Action to run [keygen, encrypt, decrypt]",
+        );
+        ap.refer(&mut day)
+            .add_option(&["-d", "--day"], Store, "Only process this day");
+        ap.refer(&mut force).add_option(
+            &["-f", "--force"],
+            StoreTrue,
+            "Overwrite existing plaintext inputs or key, or re-encrypt unchanged inputs",
+        );
+        parse_subcommand_args(ap, args);
+    }
+
+    match action.as_str() {
+        "keygen" => keygen(force),
+        "encrypt" => encrypt(day, force),
+        "decrypt" => decrypt(day, force),
+        _ => panic!("Unrecognised inputs action [{}]", action),
+    }
+}
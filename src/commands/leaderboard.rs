@@ -0,0 +1,462 @@
+use super::parse_subcommand_args;
+use advent_of_code_2022::DAYS;
+use argparse::{ArgumentParser, Store, StoreTrue};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::{fmt, fs};
+
+// puzzles unlock at midnight EST, which is 05:00 UTC
+const UNLOCK_HOUR_UTC: i64 = 5;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+const PARTS: [(&str, char); 2] = [("1", 'a'), ("2", 'b')];
+
+// the subset of the AoC private leaderboard export that we use
+#[derive(Deserialize)]
+struct Leaderboard {
+    event: String,
+    members: HashMap<String, Member>,
+    // read from `event` and the members' day keys once they're checked
+    #[serde(skip)]
+    year: i64,
+    #[serde(skip)]
+    days: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+enum LeaderboardError {
+    Json(String, String),
+    BadField {
+        file: String,
+        field: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LeaderboardError::Json(file, e) => {
+                write!(f, "{} is not a leaderboard export: {}", file, e)
+            }
+            LeaderboardError::BadField {
+                file,
+                field,
+                value,
+                expected,
+            } => write!(
+                f,
+                "{}: `{}` should be {}, found [{}]",
+                file, field, expected, value
+            ),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Member {
+    id: u64,
+    name: Option<String>,
+    local_score: u64,
+    completion_day_level: HashMap<String, HashMap<String, Star>>,
+}
+
+#[derive(Deserialize)]
+struct Star {
+    get_star_ts: i64,
+}
+
+impl Member {
+    fn display_name(&self) -> String {
+        // members without a name show up as anonymous on the AoC site
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    fn star_ts(&self, day: u32, part: char) -> Option<i64> {
+        let level = PARTS.iter().find(|(_, p)| *p == part)?.0;
+        self.completion_day_level
+            .get(&day.to_string())?
+            .get(level)
+            .map(|s| s.get_star_ts)
+    }
+}
+
+#[derive(Serialize)]
+struct StarEvent {
+    day: u32,
+    part: char,
+    timestamp: i64,
+    seconds_since_unlock: i64,
+}
+
+#[derive(Serialize)]
+struct MemberSummary {
+    name: String,
+    stars: usize,
+    local_score: u64,
+    reported_local_score: u64,
+    timeline: Vec<StarEvent>,
+    part_a_to_b_seconds: BTreeMap<u32, i64>,
+}
+
+#[derive(Serialize)]
+struct Solve {
+    name: String,
+    seconds_since_unlock: i64,
+}
+
+#[derive(Serialize)]
+struct DaySummary {
+    day: u32,
+    fastest_part_a: Option<Solve>,
+    fastest_part_b: Option<Solve>,
+}
+
+#[derive(Serialize)]
+struct Analysis {
+    event: String,
+    members: Vec<MemberSummary>,
+    days: Vec<DaySummary>,
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // days since the unix epoch for a gregorian date, see
+    // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn unlock_ts(year: i64, day: u32) -> i64 {
+    days_from_civil(year, 12, day as i64) * SECONDS_PER_DAY + UNLOCK_HOUR_UTC * 3600
+}
+
+fn format_seconds(seconds: i64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+fn local_scores(members: &[&Member], days: &[u32]) -> HashMap<u64, u64> {
+    // each star is worth one point per member on the leaderboard, minus one
+    // for every member who got that star first
+    let mut scores: HashMap<u64, u64> = members.iter().map(|m| (m.id, 0)).collect();
+    for day in days {
+        for (_, part) in PARTS {
+            let mut solves: Vec<(i64, u64)> = members
+                .iter()
+                .filter_map(|m| m.star_ts(*day, part).map(|ts| (ts, m.id)))
+                .collect();
+            solves.sort();
+            for (rank, (_, id)) in solves.iter().enumerate() {
+                *scores.get_mut(id).unwrap() += (members.len() - rank) as u64;
+            }
+        }
+    }
+    scores
+}
+
+fn fastest(members: &[&Member], year: i64, day: u32, part: char) -> Option<Solve> {
+    members
+        .iter()
+        .filter_map(|m| m.star_ts(day, part).map(|ts| (ts, m)))
+        .min_by_key(|(ts, _)| *ts)
+        .map(|(ts, m)| Solve {
+            name: m.display_name(),
+            seconds_since_unlock: ts - unlock_ts(year, day),
+        })
+}
+
+fn load(file: &str, contents: &str) -> Result<Leaderboard, LeaderboardError> {
+    let mut leaderboard: Leaderboard = serde_json::from_str(contents)
+        .map_err(|e| LeaderboardError::Json(file.to_string(), e.to_string()))?;
+    let bad_field = |field: String, value: &str, expected| LeaderboardError::BadField {
+        file: file.to_string(),
+        field,
+        value: value.to_string(),
+        expected,
+    };
+
+    leaderboard.year = leaderboard
+        .event
+        .parse()
+        .map_err(|_| bad_field("event".to_string(), &leaderboard.event, "a year"))?;
+    for (key, member) in &leaderboard.members {
+        for day in member.completion_day_level.keys() {
+            match day.parse::<u32>() {
+                Ok(d) if DAYS.contains(&d) => leaderboard.days.push(d),
+                _ => {
+                    let field = format!("members.{}.completion_day_level", key);
+                    return Err(bad_field(field, day, "a day from 1 to 25"));
+                }
+            }
+        }
+    }
+    leaderboard.days.sort();
+    leaderboard.days.dedup();
+    Ok(leaderboard)
+}
+
+fn analyse(leaderboard: &Leaderboard) -> Analysis {
+    let (year, days) = (leaderboard.year, &leaderboard.days);
+    let members: Vec<&Member> = leaderboard.members.values().collect();
+
+    let scores = local_scores(&members, days);
+
+    let mut member_summaries: Vec<MemberSummary> = members
+        .iter()
+        .map(|m| {
+            let mut timeline: Vec<StarEvent> = days
+                .iter()
+                .flat_map(|d| PARTS.iter().map(move |(_, p)| (*d, *p)))
+                .filter_map(|(day, part)| {
+                    m.star_ts(day, part).map(|ts| StarEvent {
+                        day,
+                        part,
+                        timestamp: ts,
+                        seconds_since_unlock: ts - unlock_ts(year, day),
+                    })
+                })
+                .collect();
+            timeline.sort_by_key(|e| e.timestamp);
+
+            let part_a_to_b_seconds = days
+                .iter()
+                .filter_map(|d| match (m.star_ts(*d, 'a'), m.star_ts(*d, 'b')) {
+                    (Some(a), Some(b)) => Some((*d, b - a)),
+                    _ => None,
+                })
+                .collect();
+
+            MemberSummary {
+                name: m.display_name(),
+                stars: timeline.len(),
+                local_score: scores[&m.id],
+                reported_local_score: m.local_score,
+                timeline,
+                part_a_to_b_seconds,
+            }
+        })
+        .collect();
+    member_summaries.sort_by(|a, b| {
+        b.local_score
+            .cmp(&a.local_score)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let day_summaries = days
+        .iter()
+        .map(|d| DaySummary {
+            day: *d,
+            fastest_part_a: fastest(&members, year, *d, 'a'),
+            fastest_part_b: fastest(&members, year, *d, 'b'),
+        })
+        .collect();
+
+    Analysis {
+        event: leaderboard.event.clone(),
+        members: member_summaries,
+        days: day_summaries,
+    }
+}
+
+fn print_tables(analysis: &Analysis) {
+    println!("Private leaderboard {}\n", analysis.event);
+
+    println!("Local scores");
+    println!(
+        "{:>4}  {:<24}  {:>5}  {:>5}  {:>8}",
+        "Rank", "Member", "Stars", "Score", "Reported"
+    );
+    for (rank, m) in analysis.members.iter().enumerate() {
+        println!(
+            "{:>4}  {:<24}  {:>5}  {:>5}  {:>8}",
+            rank + 1,
+            m.name,
+            m.stars,
+            m.local_score,
+            m.reported_local_score
+        );
+    }
+
+    println!("\nStar timelines (time since the puzzle unlocked)");
+    for m in &analysis.members {
+        let timeline: Vec<String> = m
+            .timeline
+            .iter()
+            .map(|e| {
+                format!(
+                    "{}{} {}",
+                    e.day,
+                    e.part,
+                    format_seconds(e.seconds_since_unlock)
+                )
+            })
+            .collect();
+        println!("{:<24}  {}", m.name, timeline.join(", "));
+    }
+
+    println!("\nTime from part a to part b");
+    for m in &analysis.members {
+        let deltas: Vec<String> = m
+            .part_a_to_b_seconds
+            .iter()
+            .map(|(day, seconds)| format!("{}: {}", day, format_seconds(*seconds)))
+            .collect();
+        println!("{:<24}  {}", m.name, deltas.join(", "));
+    }
+
+    println!("\nFastest solvers");
+    println!("{:>3}  {:<36}  {:<36}", "Day", "Part a", "Part b");
+    let describe = |solve: &Option<Solve>| match solve {
+        Some(s) => format!("{} ({})", s.name, format_seconds(s.seconds_since_unlock)),
+        None => "-".to_string(),
+    };
+    for d in &analysis.days {
+        println!(
+            "{:>3}  {:<36}  {:<36}",
+            d.day,
+            describe(&d.fastest_part_a),
+            describe(&d.fastest_part_b)
+        );
+    }
+}
+
+pub fn run(args: Vec<String>) {
+    let mut file = String::new();
+    let mut json = false;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("Analyse an exported private leaderboard JSON file.");
+        ap.refer(&mut file).required().add_argument(
+            "file",
+            Store,
+            "Path to the leaderboard JSON export",
+        );
+        ap.refer(&mut json)
+            .add_option(&["--json"], StoreTrue, "Print the analysis as JSON");
+        parse_subcommand_args(ap, args);
+    }
+
+    let contents = fs::read_to_string(&file).unwrap_or_else(|_| panic!("could not open {}", file));
+    let leaderboard = load(&file, &contents).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+
+    let analysis = analyse(&leaderboard);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&analysis).unwrap());
+    } else {
+        print_tables(&analysis);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_1_UNLOCK: i64 = 1_669_870_800;
+
+    fn leaderboard() -> Leaderboard {
+        let json = format!(
+            r#"{{
+                "event": "2022",
+                "owner_id": 1,
+                "members": {{
+                    "1": {{
+                        "id": 1, "name": "alice", "stars": 2, "local_score": 3,
+                        "completion_day_level": {{
+                            "1": {{
+                                "1": {{"get_star_ts": {a1}, "star_index": 1}},
+                                "2": {{"get_star_ts": {a2}, "star_index": 4}}
+                            }}
+                        }}
+                    }},
+                    "2": {{
+                        "id": 2, "name": null, "stars": 2, "local_score": 3,
+                        "completion_day_level": {{
+                            "1": {{
+                                "1": {{"get_star_ts": {b1}, "star_index": 2}},
+                                "2": {{"get_star_ts": {b2}, "star_index": 3}}
+                            }}
+                        }}
+                    }}
+                }}
+            }}"#,
+            a1 = DAY_1_UNLOCK + 60,
+            a2 = DAY_1_UNLOCK + 600,
+            b1 = DAY_1_UNLOCK + 120,
+            b2 = DAY_1_UNLOCK + 300,
+        );
+        load("leaderboard.json", &json).unwrap()
+    }
+
+    #[test]
+    fn test_unlock_ts() {
+        assert_eq!(unlock_ts(2022, 1), DAY_1_UNLOCK);
+        assert_eq!(unlock_ts(2022, 25), DAY_1_UNLOCK + 24 * SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn test_analyse() {
+        let analysis = analyse(&leaderboard());
+
+        let names: Vec<&str> = analysis.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["(anonymous user #2)", "alice"]);
+        for member in &analysis.members {
+            assert_eq!(member.local_score, 3);
+            assert_eq!(member.local_score, member.reported_local_score);
+        }
+        assert_eq!(analysis.members[1].part_a_to_b_seconds[&1], 540);
+
+        let fastest_a = analysis.days[0].fastest_part_a.as_ref().unwrap();
+        assert_eq!(fastest_a.name, "alice");
+        assert_eq!(fastest_a.seconds_since_unlock, 60);
+        let fastest_b = analysis.days[0].fastest_part_b.as_ref().unwrap();
+        assert_eq!(fastest_b.name, "(anonymous user #2)");
+    }
+
+    #[test]
+    fn test_load_rejects_bad_fields() {
+        let bad_field = |field: &str, value: &str, expected| LeaderboardError::BadField {
+            file: "leaderboard.json".to_string(),
+            field: field.to_string(),
+            value: value.to_string(),
+            expected,
+        };
+        let event = r#"{"event": "twenty", "members": {}}"#;
+        assert_eq!(
+            load("leaderboard.json", event).err(),
+            Some(bad_field("event", "twenty", "a year"))
+        );
+        let day = r#"{"event": "2022", "members": {"1": {"id": 1, "name": null, "local_score": 0,
+            "completion_day_level": {"day 1": {}}}}}"#;
+        assert_eq!(
+            load("leaderboard.json", day).err(),
+            Some(bad_field(
+                "members.1.completion_day_level",
+                "day 1",
+                "a day from 1 to 25"
+            ))
+        );
+        assert!(matches!(
+            load("leaderboard.json", "[]"),
+            Err(LeaderboardError::Json(..))
+        ));
+    }
+
+    #[test]
+    fn test_format_seconds() {
+        assert_eq!(format_seconds(3725), "1:02:05");
+    }
+}
@@ -0,0 +1,70 @@
+use super::parse_subcommand_args;
+use advent_of_code_2022::days;
+use advent_of_code_2022::lint::format_problem;
+use advent_of_code_2022::{input_exists, input_path, read_year_file, DEFAULT_YEAR};
+use argparse::{ArgumentParser, Store};
+use std::env;
+use std::process;
+
+fn lint_day(year: u32, solution: &days::Solution, set: Option<&str>) -> usize {
+    // returns the number of problems found in the day's input
+    let Some(lint) = solution.lint else {
+        println!("Day {} has no input validator", solution.day);
+        return 0;
+    };
+    if !input_exists(year, solution.day, set) {
+        println!("Day {} has no input", solution.day);
+        return 0;
+    }
+
+    let path = input_path(year, solution.day, set);
+    let source = path
+        .strip_prefix(env::current_dir().unwrap())
+        .unwrap_or(&path)
+        .display()
+        .to_string();
+    let input = read_year_file(year, solution.day, set);
+    let problems = lint(&input);
+    for problem in &problems {
+        println!("{}\n", format_problem(&source, &input, problem));
+    }
+    if problems.is_empty() {
+        println!("Day {} input looks fine", solution.day);
+    }
+    problems.len()
+}
+
+pub fn run(args: Vec<String>) {
+    let mut year = DEFAULT_YEAR;
+    let mut day: u32 = 0;
+    let mut set = String::new();
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("Check puzzle inputs for structural problems without solving them.");
+        ap.refer(&mut year)
+            .add_option(&["-y", "--year"], Store, "Year to check, defaults to 2022");
+        ap.refer(&mut day)
+            .add_option(&["-d", "--day"], Store, "Only check this day");
+        ap.refer(&mut set).add_option(
+            &["-s", "--set"],
+            Store,
+            "Input set to check, a directory under `inputs`",
+        );
+        parse_subcommand_args(ap, args);
+    }
+
+    let set = Some(set.as_str()).filter(|s| !s.is_empty());
+    let solutions: Vec<days::Solution> = days::registry()
+        .into_iter()
+        .filter(|s| s.year == year && (day == 0 || s.day == day))
+        .collect();
+    if solutions.is_empty() {
+        panic!("Unrecognised year [{}] day [{}]", year, day);
+    }
+
+    let problem_count: usize = solutions.iter().map(|s| lint_day(year, s, set)).sum();
+    if problem_count > 0 {
+        println!("\n{} problem(s) found", problem_count);
+        process::exit(1);
+    }
+}
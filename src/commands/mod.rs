@@ -0,0 +1,35 @@
+use advent_of_code_2022::days::Solver;
+use argparse::ArgumentParser;
+use std::io::{stderr, stdout};
+
+pub mod dashboard;
+pub mod examples;
+pub mod inputs;
+pub mod leaderboard;
+pub mod lint;
+pub mod report;
+pub mod status;
+
+// how each part shows in the status calendar and the dashboard. A part is
+// verified, and earns its star, once its latest check matched the saved answer
+pub const VERIFIED: &str = "*";
+pub const INCORRECT: &str = "x";
+pub const UNVERIFIED: &str = "?";
+pub const MISSING: &str = "-";
+
+pub fn parse_subcommand_args(ap: ArgumentParser, args: Vec<String>) {
+    // subcommands get their own parser, which is handed the remaining args
+    // with the subcommand name in place of the program name
+    if let Err(code) = ap.parse(args, &mut stdout(), &mut stderr()) {
+        std::process::exit(code);
+    }
+}
+
+pub fn star(solver: Option<Solver>, verdict: Option<bool>) -> &'static str {
+    match (solver, verdict) {
+        (None, _) => MISSING,
+        (Some(_), Some(true)) => VERIFIED,
+        (Some(_), Some(false)) => INCORRECT,
+        (Some(_), None) => UNVERIFIED,
+    }
+}
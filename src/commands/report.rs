@@ -0,0 +1,359 @@
+use super::parse_subcommand_args;
+use advent_of_code_2022::alloc_counter::allocations;
+use advent_of_code_2022::days;
+use advent_of_code_2022::render::{Image, DEFAULT_SCALE};
+use advent_of_code_2022::runtimes::format_runtime;
+use advent_of_code_2022::{input_exists, read_year_file, DEFAULT_YEAR, PARTS};
+use argparse::{ArgumentParser, Store, StoreTrue};
+use std::any::Any;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// the report replaces everything between these markers in README.md
+const REPORT_START: &str = "<!-- report:start -->";
+const REPORT_END: &str = "<!-- report:end -->";
+const README: &str = "README.md";
+
+const REDACTED: &str = "redacted";
+const NO_INPUT: &str = "no input";
+const NOT_IMPLEMENTED: &str = "not implemented";
+
+struct PartResult {
+    // the answer, or why there isn't one
+    answer: Result<String, String>,
+    runtime: Duration,
+    allocations: usize,
+}
+
+struct DayReport {
+    day: u32,
+    parse_time: Duration,
+    parts: Option<Vec<PartResult>>,
+    render: Option<Image>,
+}
+
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    // a day that panics on its input fails its own cells, not the whole report
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| days::panic_message(payload.as_ref()))
+}
+
+fn run_part(solver: days::Solver, parsed: &dyn Any) -> PartResult {
+    let allocations_before = allocations();
+    let start = Instant::now();
+    let answer = match catch(|| solver.solve(parsed, days::Options::default())) {
+        Ok(Ok(answer)) => Ok(answer.to_string()),
+        Ok(Err(error)) => Err(error.to_string()),
+        Err(message) => Err(message),
+    };
+    PartResult {
+        runtime: start.elapsed(),
+        allocations: allocations() - allocations_before,
+        answer,
+    }
+}
+
+fn run_days(year: u32, set: Option<&str>, with_renders: bool) -> Vec<DayReport> {
+    days::registry()
+        .iter()
+        .filter(|solution| solution.year == year)
+        .map(|solution| {
+            if !input_exists(year, solution.day, set) {
+                return DayReport {
+                    day: solution.day,
+                    parse_time: Duration::ZERO,
+                    parts: None,
+                    render: None,
+                };
+            }
+            let input = read_year_file(year, solution.day, set);
+            let start = Instant::now();
+            let parsed = catch(|| (solution.parse)(&input));
+            let parse_time = start.elapsed();
+            let parts = PARTS
+                .iter()
+                .map(|p| match (solution.part(*p), &parsed) {
+                    (Some(solver), Ok(parsed)) => run_part(solver, parsed.as_ref()),
+                    (Some(_), Err(message)) => PartResult {
+                        answer: Err(message.clone()),
+                        runtime: Duration::ZERO,
+                        allocations: 0,
+                    },
+                    (None, _) => PartResult {
+                        answer: Ok(NOT_IMPLEMENTED.to_string()),
+                        runtime: Duration::ZERO,
+                        allocations: 0,
+                    },
+                })
+                .collect();
+            let render = solution.render.filter(|_| with_renders).and_then(|render| {
+                match catch(|| render(&input)) {
+                    Ok(image) => Some(image),
+                    Err(message) => {
+                        eprintln!("Could not render day {}: {}", solution.day, message);
+                        None
+                    }
+                }
+            });
+            DayReport {
+                day: solution.day,
+                parse_time,
+                parts: Some(parts),
+                render,
+            }
+        })
+        .collect()
+}
+
+fn answer_cell(part: &PartResult, redact: bool) -> String {
+    match &part.answer {
+        _ if redact => REDACTED.to_string(),
+        // multi-line answers (day 10's CRT) don't fit in a table cell
+        Ok(answer) if answer.contains('\n') => "see render".to_string(),
+        Ok(answer) => format!("`{}`", answer.replace('|', "\\|")),
+        // e.g. the OCR's picture of the glyph it couldn't read
+        Err(message) => format!(
+            "error: {}",
+            message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .replace('|', "\\|")
+        ),
+    }
+}
+
+fn markdown(year: u32, reports: &[DayReport], redact: bool, render_dir: &str) -> String {
+    let mut lines = vec![
+        format!("_{} results, generated with `cargo run -- report`_", year),
+        String::new(),
+        "| Day | Part a | Part b | Parse | Runtime a | Runtime b | Allocations a | Allocations b |"
+            .to_string(),
+        "|---:|---|---|---:|---:|---:|---:|---:|".to_string(),
+    ];
+    for report in reports {
+        lines.push(match &report.parts {
+            Some(parts) => format!(
+                "| {} | {} | {} | {} | {} | {} | {} | {} |",
+                report.day,
+                answer_cell(&parts[0], redact),
+                answer_cell(&parts[1], redact),
+                format_runtime(&report.parse_time),
+                format_runtime(&parts[0].runtime),
+                format_runtime(&parts[1].runtime),
+                parts[0].allocations,
+                parts[1].allocations
+            ),
+            None => format!(
+                "| {} | {} | {} | - | - | - | - | - |",
+                report.day, NO_INPUT, NO_INPUT
+            ),
+        });
+    }
+    for report in reports.iter().filter(|r| r.render.is_some()) {
+        lines.push(String::new());
+        lines.push(format!("#### Day {}", report.day));
+        lines.push(String::new());
+        lines.push(format!(
+            "![day {}]({}/day_{}.svg)",
+            report.day, render_dir, report.day
+        ));
+    }
+    lines.join("\n")
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn html(year: u32, reports: &[DayReport], redact: bool) -> String {
+    let mut rows: Vec<String> = Vec::new();
+    for report in reports {
+        rows.push(match &report.parts {
+            Some(parts) => {
+                let answer = |p: &PartResult| match &p.answer {
+                    _ if redact => REDACTED.to_string(),
+                    Ok(answer) => format!("<pre>{}</pre>", escape_html(answer.trim_start())),
+                    Err(message) => format!("<pre>error: {}</pre>", escape_html(message)),
+                };
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    report.day,
+                    answer(&parts[0]),
+                    answer(&parts[1]),
+                    format_runtime(&report.parse_time),
+                    format_runtime(&parts[0].runtime),
+                    format_runtime(&parts[1].runtime),
+                    parts[0].allocations,
+                    parts[1].allocations
+                )
+            }
+            None => format!(
+                "<tr><td>{}</td><td colspan=\"7\">{}</td></tr>",
+                report.day, NO_INPUT
+            ),
+        });
+    }
+    let renders: Vec<String> = reports
+        .iter()
+        .filter_map(|r| {
+            r.render
+                .as_ref()
+                .map(|image| format!("<h2>Day {}</h2>\n{}", r.day, image.to_svg(DEFAULT_SCALE)))
+        })
+        .collect();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Advent of Code {}</title>
+<style>
+body {{ font-family: sans-serif; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #ccc; padding: 4px 8px; text-align: right; vertical-align: top; }}
+pre {{ margin: 0; text-align: left; }}
+</style>
+</head>
+<body>
+<h1>Advent of Code {}</h1>
+<table>
+<tr><th>Day</th><th>Part a</th><th>Part b</th><th>Parse</th><th>Runtime a</th><th>Runtime b</th><th>Allocations a</th><th>Allocations b</th></tr>
+{}
+</table>
+{}
+</body>
+</html>
+"#,
+        year,
+        year,
+        rows.join("\n"),
+        renders.join("\n")
+    )
+}
+
+fn replace_between_markers(document: &str, content: &str) -> String {
+    let section = format!("{}\n{}\n{}", REPORT_START, content, REPORT_END);
+    match (document.find(REPORT_START), document.find(REPORT_END)) {
+        (Some(start), Some(end)) if start < end => format!(
+            "{}{}{}",
+            &document[..start],
+            section,
+            &document[end + REPORT_END.len()..]
+        ),
+        // first run, add the markers at the end
+        _ => format!("{}\n\n## Report\n\n{}\n", document.trim_end(), section),
+    }
+}
+
+pub fn run(args: Vec<String>) {
+    let mut year = DEFAULT_YEAR;
+    let mut html_path = String::new();
+    let mut redact = false;
+    let mut set = String::new();
+    let mut render_dir = String::from("report");
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description(
+            "Run every registered day and write a report of answers, timings and renders.",
+        );
+        ap.refer(&mut year).add_option(
+            &["-y", "--year"],
+            Store,
+            "Year to report, defaults to 2022",
+        );
+        ap.refer(&mut html_path).add_option(
+            &["--html"],
+            Store,
+            "Write a standalone HTML page here instead of updating README.md",
+        );
+        ap.refer(&mut redact)
+            .add_option(&["--redact"], StoreTrue, "Hide answers and renders");
+        ap.refer(&mut set).add_option(
+            &["-s", "--set"],
+            Store,
+            "Input set to run, a directory under `inputs`",
+        );
+        ap.refer(&mut render_dir).add_option(
+            &["--render-dir"],
+            Store,
+            "Directory for the README's rendered images",
+        );
+        parse_subcommand_args(ap, args);
+    }
+
+    let set = Some(set.as_str()).filter(|s| !s.is_empty());
+    // renders can give the answers away, e.g. day 10's CRT
+    let reports = run_days(year, set, !redact);
+
+    if !html_path.is_empty() {
+        fs::write(&html_path, html(year, &reports, redact)).unwrap();
+        println!("Wrote report to {}", html_path);
+        return;
+    }
+
+    fs::create_dir_all(&render_dir).unwrap();
+    for report in &reports {
+        if let Some(image) = &report.render {
+            let path = Path::new(&render_dir).join(format!("day_{}.svg", report.day));
+            image.write(&path, DEFAULT_SCALE);
+        }
+    }
+    let readme = fs::read_to_string(README).unwrap_or_default();
+    let updated = replace_between_markers(&readme, &markdown(year, &reports, redact, &render_dir));
+    fs::write(README, updated).unwrap();
+    println!("Updated report in {}", README);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2022::read_test_file;
+
+    #[test]
+    fn test_replace_between_markers() {
+        let document = format!("# Title\n{}\nold\n{}\nfooter\n", REPORT_START, REPORT_END);
+        assert_eq!(
+            replace_between_markers(&document, "new"),
+            format!("# Title\n{}\nnew\n{}\nfooter\n", REPORT_START, REPORT_END)
+        );
+    }
+
+    #[test]
+    fn test_replace_between_markers_appends_when_missing() {
+        assert_eq!(
+            replace_between_markers("# Title\n", "new"),
+            format!(
+                "# Title\n\n## Report\n\n{}\nnew\n{}\n",
+                REPORT_START, REPORT_END
+            )
+        );
+    }
+
+    #[test]
+    fn test_answer_cell() {
+        let part = PartResult {
+            answer: Ok("\n##..\n..##".to_string()),
+            runtime: Duration::from_millis(1),
+            allocations: 0,
+        };
+        assert_eq!(answer_cell(&part, false), "see render");
+        assert_eq!(answer_cell(&part, true), REDACTED);
+    }
+
+    #[test]
+    fn test_run_part_reports_failures() {
+        let part = run_part(days::Solver::Builtin(|_, _| panic!("bad input")), &());
+        assert_eq!(answer_cell(&part, false), "error: bad input");
+
+        // the example's CRT is a test pattern, which can't be read
+        let solution = days::find(DEFAULT_YEAR, 10).unwrap();
+        let parsed = (solution.parse)(&read_test_file(10));
+        let part = run_part(solution.part('b').unwrap(), parsed.as_ref());
+        assert!(answer_cell(&part, false).starts_with("error: could not read the letters"));
+    }
+}
@@ -0,0 +1,93 @@
+use super::{parse_subcommand_args, star, INCORRECT, MISSING, UNVERIFIED, VERIFIED};
+use advent_of_code_2022::checks::Checks;
+use advent_of_code_2022::days;
+use advent_of_code_2022::runtimes::{format_runtime, Runtimes};
+use advent_of_code_2022::{input_exists, set_dir, DAYS, DEFAULT_YEAR, PARTS, TEST_SET};
+use argparse::{ArgumentParser, Store};
+use std::env;
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+pub fn run(args: Vec<String>) {
+    let mut year = DEFAULT_YEAR;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("Show a calendar of solved days, stars and missing pieces.");
+        ap.refer(&mut year)
+            .add_option(&["-y", "--year"], Store, "Year to show, defaults to 2022");
+        parse_subcommand_args(ap, args);
+    }
+
+    // stars are for the real inputs' answers
+    let checks = Checks::load(&set_dir(year, None));
+    let runtimes = Runtimes::load(&env::current_dir().unwrap());
+    let solutions: Vec<days::Solution> = days::registry()
+        .into_iter()
+        .filter(|s| s.year == year)
+        .collect();
+
+    println!("Advent of Code {}\n", year);
+    println!(
+        "{:>3}  {:<6}  {:<2} {:<2}  {:<5}  {:<5}  {:>10}  {:>10}",
+        "Day", "Solver", "a", "b", "Input", "Test", "Runtime a", "Runtime b"
+    );
+
+    let mut stars = 0;
+    for day in DAYS {
+        let solution = solutions.iter().find(|s| s.day == day);
+        let implemented = solution.is_some();
+        let has_input = input_exists(year, day, None);
+        let has_test_input = input_exists(year, day, Some(TEST_SET));
+
+        let verified: Vec<&str> = PARTS
+            .iter()
+            .map(|p| star(solution.and_then(|s| s.part(*p)), checks.get(day, *p)))
+            .collect();
+        stars += verified.iter().filter(|v| **v == VERIFIED).count();
+
+        let day_runtimes: Vec<String> = PARTS
+            .iter()
+            .map(|p| {
+                runtimes
+                    .get(year, day, *p)
+                    .map_or(MISSING.to_string(), format_runtime)
+            })
+            .collect();
+
+        if implemented {
+            println!(
+                "{:>3}  {:<6}  {:<2} {:<2}  {:<5}  {:<5}  {:>10}  {:>10}",
+                day,
+                "yes",
+                verified[0],
+                verified[1],
+                yes_no(has_input),
+                yes_no(has_test_input),
+                day_runtimes[0],
+                day_runtimes[1]
+            );
+        } else {
+            println!(
+                "{:>3}  {:<6}  {:<2} {:<2}  {:<5}  {:<5}  not implemented",
+                day,
+                "no",
+                verified[0],
+                verified[1],
+                yes_no(has_input),
+                yes_no(has_test_input)
+            );
+        }
+    }
+
+    println!(
+        "\n{} verified by --check, {} incorrect, {} not checked, {} not implemented",
+        VERIFIED, INCORRECT, UNVERIFIED, MISSING
+    );
+    println!("Stars: {}/{}", stars, DAYS.count() * PARTS.len());
+}
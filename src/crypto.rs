@@ -0,0 +1,204 @@
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::path::{Path, PathBuf};
+use std::{env, fmt, fs};
+
+// the key file lives in the repo root and is gitignored. It can be pointed
+// elsewhere (e.g. a CI secret mount) with the AOC_KEY_FILE env var
+pub const KEY_FILE: &str = ".aoc_key";
+pub const KEY_FILE_ENV_VAR: &str = "AOC_KEY_FILE";
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Debug, PartialEq)]
+pub enum CryptoError {
+    MissingKey(PathBuf),
+    InvalidKey(PathBuf),
+    // the key file can be read by other users, holding the unix mode
+    LoosePermissions(PathBuf, u32),
+    Truncated,
+    DecryptionFailed,
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CryptoError::MissingKey(path) => write!(
+                f,
+                "key file {} not found. Ask a teammate for the key, set {} or run `inputs keygen`",
+                path.display(),
+                KEY_FILE_ENV_VAR
+            ),
+            CryptoError::InvalidKey(path) => write!(
+                f,
+                "key file {} must contain {} hex encoded bytes",
+                path.display(),
+                KEY_LENGTH
+            ),
+            CryptoError::LoosePermissions(path, mode) => write!(
+                f,
+                "key file {} is accessible by other users (mode {:o}), run `chmod 600 {}`",
+                path.display(),
+                mode,
+                path.display()
+            ),
+            CryptoError::Truncated => write!(f, "encrypted input is too short to be valid"),
+            CryptoError::DecryptionFailed => {
+                write!(f, "decryption failed, the key does not match this input")
+            }
+        }
+    }
+}
+
+pub fn key_path() -> PathBuf {
+    match env::var(KEY_FILE_ENV_VAR) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => env::current_dir().unwrap().join(KEY_FILE),
+    }
+}
+
+pub fn encrypted_path(plaintext_path: &Path) -> PathBuf {
+    let mut filename = plaintext_path.as_os_str().to_owned();
+    filename.push(".");
+    filename.push(ENCRYPTED_EXTENSION);
+    PathBuf::from(filename)
+}
+
+pub fn generate_key() -> Vec<u8> {
+    ChaCha20Poly1305::generate_key(&mut OsRng).to_vec()
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), CryptoError> {
+    use std::os::unix::fs::PermissionsExt;
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(());
+    };
+    let mode = metadata.permissions().mode() & 0o777;
+    if mode & 0o077 != 0 {
+        return Err(CryptoError::LoosePermissions(path.to_path_buf(), mode));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<(), CryptoError> {
+    Ok(())
+}
+
+pub fn write_key(path: &Path, key: &[u8]) -> std::io::Result<()> {
+    // only the owner may read the key. The mode only applies to new files,
+    // so an existing key is removed rather than overwritten
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    let mut file = options.open(path)?;
+    std::io::Write::write_all(&mut file, (to_hex(key) + "\n").as_bytes())
+}
+
+pub fn load_key(path: &Path) -> Result<Vec<u8>, CryptoError> {
+    let contents =
+        fs::read_to_string(path).map_err(|_| CryptoError::MissingKey(path.to_path_buf()))?;
+    check_permissions(path)?;
+    from_hex(contents.trim())
+        .filter(|key| key.len() == KEY_LENGTH)
+        .ok_or_else(|| CryptoError::InvalidKey(path.to_path_buf()))
+}
+
+pub fn encrypt(key: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    // output layout is the random nonce followed by the ciphertext
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).unwrap();
+    [nonce.to_vec(), ciphertext].concat()
+}
+
+pub fn decrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if data.len() < NONCE_LENGTH {
+        return Err(CryptoError::Truncated);
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| CryptoError::DecryptionFailed)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = generate_key();
+        let encrypted = encrypt(&key, b"1000\n2000\n\n3000");
+        assert_eq!(decrypt(&key, &encrypted).unwrap(), b"1000\n2000\n\n3000");
+    }
+
+    #[test]
+    fn test_decrypt_with_wrong_key() {
+        let encrypted = encrypt(&generate_key(), b"noop");
+        assert_eq!(
+            decrypt(&generate_key(), &encrypted),
+            Err(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let key = generate_key();
+        assert_eq!(from_hex(&to_hex(&key)), Some(key));
+        assert_eq!(from_hex("zz"), None);
+    }
+
+    #[test]
+    fn test_missing_key() {
+        let path = Path::new("does/not/exist");
+        assert_eq!(
+            load_key(path),
+            Err(CryptoError::MissingKey(path.to_path_buf()))
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_key_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = env::temp_dir().join(format!("aoc_key_test_{}", std::process::id()));
+        let key = generate_key();
+        write_key(&path, &key).unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(load_key(&path), Ok(key));
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert_eq!(
+            load_key(&path),
+            Err(CryptoError::LoosePermissions(path.clone(), 0o644))
+        );
+        // replacing a key tightens the permissions again
+        write_key(&path, &generate_key()).unwrap();
+        assert_eq!(mode(&path), 0o600);
+        fs::remove_file(&path).unwrap();
+    }
+}
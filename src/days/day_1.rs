@@ -1,32 +1,92 @@
-use advent_of_code_2022::to_u32;
+use crate::arithmetic::{self, Overflow};
+use crate::lint::{check_not_empty, check_number, Problem};
+use crate::parser::{expect_parse, integer, lines, paragraphs};
+use crate::stream::{for_each_line, parse_line};
+use std::io::BufRead;
 
-fn sum_of_snacks(snacks: &str) -> u32 {
-    snacks.lines().map(to_u32).sum()
-}
+const DAY: u32 = 1;
+
+// total calories carried by each elf, most first. Totalling can overflow,
+// which each part then reports
+pub struct Calories(Result<Vec<u32>, Overflow>);
 
-pub fn part_a(input: &str) -> u32 {
-    let snacks_per_elf = input.split("\n\n").collect::<Vec<&str>>();
-    let calories_per_elf = snacks_per_elf
+pub fn parse(input: &str) -> Calories {
+    // one snack per line, elves separated by blank lines
+    let snacks_per_elf: Vec<Vec<u32>> = expect_parse(paragraphs(lines(integer())), input);
+    let calories_per_elf: Result<Vec<u32>, Overflow> = snacks_per_elf
         .iter()
-        .map(|x| sum_of_snacks(*x))
-        .collect::<Vec<u32>>();
-    *calories_per_elf.iter().max().unwrap()
+        .map(|s| arithmetic::sum(DAY, "calories carried by an elf", s.iter().copied()))
+        .collect();
+    Calories(calories_per_elf.map(|mut calories| {
+        calories.sort_by(|a, b| b.cmp(a));
+        calories
+    }))
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let snacks_per_elf = input.split("\n\n").collect::<Vec<&str>>();
-    let mut calories_per_elf = snacks_per_elf
-        .iter()
-        .map(|x| sum_of_snacks(*x))
-        .collect::<Vec<u32>>();
-    calories_per_elf.sort();
-    calories_per_elf.iter().rev().take(3).sum::<u32>()
+impl Calories {
+    fn totals(&self) -> Result<&[u32], Overflow> {
+        self.0.as_deref().map_err(Clone::clone)
+    }
+}
+
+pub fn part_a(calories: &Calories) -> Result<u32, Overflow> {
+    Ok(calories.totals()?[0])
+}
+
+pub fn part_b(calories: &Calories) -> Result<u32, Overflow> {
+    arithmetic::sum(
+        DAY,
+        "calories of the top three elves",
+        calories.totals()?.iter().take(3).copied(),
+    )
+}
+
+fn keep_top_three(top: &mut [u32; 3], total: u32) {
+    // `top` stays sorted most first
+    if total > top[2] {
+        top[2] = total;
+        top.sort_by(|a, b| b.cmp(a));
+    }
+}
+
+pub fn solve_reader(part: char, reader: impl BufRead) -> Result<u32, Overflow> {
+    // only the running total and the three largest totals are kept. After an
+    // overflow the rest of the input is still read, to report bad lines
+    let mut top = [0; 3];
+    let mut current = Ok(0);
+    for_each_line(reader, |idx, line| {
+        if line.is_empty() {
+            if let Ok(total) = current {
+                keep_top_three(&mut top, total);
+                current = Ok(0);
+            }
+        } else {
+            let snack = parse_line(integer(), idx, line);
+            current = current
+                .clone()
+                .and_then(|c| arithmetic::add(DAY, "calories carried by an elf", c, snack));
+        }
+    });
+    keep_top_three(&mut top, current?);
+    match part {
+        'a' => Ok(top[0]),
+        _ => arithmetic::sum(DAY, "calories of the top three elves", top),
+    }
+}
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    // one calorie count per line, elves separated by blank lines
+    let mut problems: Vec<Problem> = check_not_empty(input).into_iter().collect();
+    for (idx, line) in input.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+        problems.extend(check_number::<u32>(idx, 0, line));
+    }
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::{read_test_file, to_u32};
 
     #[test]
     fn test_to_u32() {
@@ -40,22 +100,32 @@ mod tests {
     }
 
     #[test]
-    fn test_sum_of_snacks() {
-        let snacks = "1
-            2
-            100";
-        assert_eq!(sum_of_snacks(snacks), 103);
+    fn test_parse() {
+        assert_eq!(parse("1\n2\n\n100\n").0, Ok(vec![100, 3]));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid input at line 2, column 1: expected a number")]
+    fn test_parse_bad_input() {
+        parse("1\nabc\n");
     }
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(1);
-        assert_eq!(part_a(&input), 101);
+        assert_eq!(part_a(&parse(&input)), Ok(101));
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(1);
-        assert_eq!(part_b(&input), 115);
+        assert_eq!(part_b(&parse(&input)), Ok(115));
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let input = read_test_file(1);
+        assert_eq!(solve_reader('a', input.as_bytes()), Ok(101));
+        assert_eq!(solve_reader('b', input.as_bytes()), Ok(115));
     }
 }
@@ -1,8 +1,16 @@
-use std::str::FromStr;
+use crate::animation::Frame;
+use crate::grid::Grid;
+use crate::lint::{check_not_empty, check_number, Problem};
+use crate::ocr::{self, OcrError};
+use crate::parser::{expect_parse, integer, lines, literal, map, preceded, value, Parser};
+use crate::render::{Image, BLACK, GREEN};
+use crate::simulation::{Driver, Simulation};
+use crate::ReturnType;
+use crate::{alt, explain};
 use Instruction::{AddX, Noop};
 
-const ADDX_REPR: &'static str = "addx";
-const NOOP_REPR: &'static str = "noop";
+const ADDX_REPR: &str = "addx ";
+const NOOP_REPR: &str = "noop";
 
 const ADDX_CYCLES: i64 = 2;
 const NOOP_CYCLES: i64 = 1;
@@ -13,7 +21,7 @@ const CYCLE_ANALYSIS_PERIOD: i64 = 40;
 const SCREEN_HEIGHT: usize = 6;
 const SCREEN_WIDTH: usize = 40;
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 enum Instruction {
     AddX { val: i64 },
     Noop,
@@ -28,68 +36,111 @@ impl Instruction {
     }
 }
 
-impl FromStr for Instruction {
-    type Err = ();
+fn instruction<'a>() -> impl Parser<'a, Instruction> {
+    alt!(
+        map(preceded(literal(ADDX_REPR), integer()), |val| AddX { val }),
+        value(literal(NOOP_REPR), Noop),
+    )
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with(ADDX_REPR) {
-            let val = s[5..].parse::<i64>().unwrap();
-            Ok(AddX { val })
-        } else if s == NOOP_REPR {
-            Ok(Noop)
-        } else {
-            Err(())
-        }
-    }
+pub struct Program(Vec<Instruction>);
+
+pub fn parse(input: &str) -> Program {
+    Program(expect_parse(lines(instruction()), input))
 }
 
+#[derive(Clone, Copy)]
 struct SystemState {
     x_register: i64,
     clock_cycles: i64,
 }
 
-fn execute(instructions: Vec<Instruction>) -> Vec<SystemState> {
-    // returns a vector of system states, where each state is a snapshot of the
-    // system at a given clock cycle
-    let mut x_register: i64 = 1;
-    let mut clock_cycles: i64 = 0;
-
-    let starting_state = SystemState {
-        x_register: x_register,
-        clock_cycles: clock_cycles,
-    };
-
-    let mut system_states: Vec<SystemState> = Vec::from([starting_state]);
-
-    for instruction in instructions {
-        // account for clock ticks where nothing happens
-        for _ in 0..instruction.cycles() {
-            clock_cycles += 1;
-            let state = SystemState {
-                x_register: x_register,
-                clock_cycles: clock_cycles,
-            };
-            system_states.push(state);
+// the next instruction, its cycles left, X and the state during the cycle
+type CpuSnapshot = (usize, i64, i64, SystemState);
+
+// runs the program one clock cycle per step. The state is the X register
+// during the cycle, an addx only lands once its last cycle is over
+struct Cpu<'a> {
+    instructions: &'a [Instruction],
+    next: usize,
+    // cycles left of the instruction being executed
+    remaining: i64,
+    x_register: i64,
+    state: SystemState,
+}
+
+impl<'a> Cpu<'a> {
+    fn new(instructions: &'a [Instruction]) -> Cpu<'a> {
+        Cpu {
+            instructions,
+            next: 0,
+            remaining: 0,
+            x_register: 1,
+            state: SystemState {
+                x_register: 1,
+                clock_cycles: 0,
+            },
         }
+    }
+}
+
+impl Simulation for Cpu<'_> {
+    type State = SystemState;
+    type Snapshot = CpuSnapshot;
 
-        // actually execute the instruction
-        match instruction {
-            AddX { val } => {
-                x_register += val;
+    fn step(&mut self) -> bool {
+        if self.remaining == 0 {
+            let Some(instruction) = self.instructions.get(self.next) else {
+                return false;
+            };
+            self.next += 1;
+            self.remaining = instruction.cycles();
+        }
+        self.state = SystemState {
+            x_register: self.x_register,
+            clock_cycles: self.state.clock_cycles + 1,
+        };
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            // actually execute the instruction
+            let cycle = self.state.clock_cycles;
+            explain!(State, "executed instruction"; cycle = cycle, x = self.x_register);
+            if let AddX { val } = self.instructions[self.next - 1] {
+                self.x_register += val;
             }
-            Noop => (),
         }
+        true
+    }
+
+    fn state(&self) -> &SystemState {
+        &self.state
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.next, self.remaining, self.x_register, self.state)
+    }
+
+    fn restore(&mut self, &(next, remaining, x_register, state): &CpuSnapshot) {
+        self.next = next;
+        self.remaining = remaining;
+        self.x_register = x_register;
+        self.state = state;
     }
-    system_states
 }
 
-pub fn part_a(input: &str) -> i64 {
-    let instructions: Vec<Instruction> = input
-        .lines()
-        .map(|l| Instruction::from_str(l).unwrap())
-        .collect();
+fn execute(instructions: &[Instruction]) -> Vec<SystemState> {
+    // returns a vector of system states, where each state is a snapshot of the
+    // system at a given clock cycle
+    let mut driver = Driver::new(Cpu::new(instructions));
+    let mut system_states = vec![*driver.state()];
+    while driver.step() {
+        system_states.push(*driver.state());
+    }
+    system_states
+}
 
-    let system_states = execute(instructions);
+pub fn part_a(program: &Program) -> i64 {
+    let system_states = execute(&program.0);
 
     let filtered_system_states: Vec<&SystemState> = system_states
         .iter()
@@ -98,59 +149,182 @@ pub fn part_a(input: &str) -> i64 {
 
     filtered_system_states
         .iter()
-        .map(|s| s.x_register * s.clock_cycles)
+        .map(|s| {
+            explain!(
+                Decision,
+                "cycle {} has signal strength {}",
+                s.clock_cycles,
+                s.x_register * s.clock_cycles
+            );
+            s.x_register * s.clock_cycles
+        })
         .sum()
 }
 
-pub fn part_b(input: &str) -> String {
-    let instructions: Vec<Instruction> = input
-        .lines()
-        .map(|l| Instruction::from_str(l).unwrap())
-        .collect();
+// the CRT draws one pixel per clock cycle of the CPU driving it
+struct Crt<'a> {
+    cpu: Cpu<'a>,
+    screen: Grid<char>,
+}
 
-    let system_states = &execute(instructions)[1..];
+impl Crt<'_> {
+    fn beam(&self) -> (usize, usize) {
+        // row and col of the pixel drawn during the current cycle
+        let pixel = self.cpu.state.clock_cycles as usize - 1;
+        ((pixel / SCREEN_WIDTH) % SCREEN_HEIGHT, pixel % SCREEN_WIDTH)
+    }
+}
 
-    let mut screen: [[char; SCREEN_WIDTH]; SCREEN_HEIGHT] = [['.'; SCREEN_WIDTH]; SCREEN_HEIGHT];
+impl Simulation for Crt<'_> {
+    type State = Grid<char>;
+    type Snapshot = (CpuSnapshot, Grid<char>);
 
-    for state in system_states {
-        let sprite_coverage_window: Vec<i64> =
-            ((state.x_register - 1)..(state.x_register + 2)).collect();
-        let row = ((state.clock_cycles as usize - 1) / SCREEN_WIDTH) % SCREEN_HEIGHT;
-        let col = (state.clock_cycles - 1) as usize % SCREEN_WIDTH;
+    fn step(&mut self) -> bool {
+        if !self.cpu.step() {
+            return false;
+        }
+        let x_register = self.cpu.state.x_register;
+        let (row, col) = self.beam();
+        if (x_register - 1..=x_register + 1).contains(&(col as i64)) {
+            self.screen[(col, row)] = '#';
+        }
+        true
+    }
 
-        if sprite_coverage_window.contains(&(col as i64)) {
-            // row and col of current pixel that is being drawn
-            screen[row][col] = '#';
+    fn state(&self) -> &Grid<char> {
+        &self.screen
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.cpu.snapshot(), self.screen.clone())
+    }
+
+    fn restore(&mut self, (cpu, screen): &Self::Snapshot) {
+        self.cpu.restore(cpu);
+        self.screen = screen.clone();
+    }
+
+    fn frame(&self) -> Option<Frame> {
+        if self.cpu.state.clock_cycles == 0 {
+            return None;
+        }
+        let (row, col) = self.beam();
+        Some(screen_frame(&self.screen, &self.cpu.state, row, col))
+    }
+}
+
+fn draw_screen(instructions: &[Instruction]) -> Grid<char> {
+    let mut driver = Driver::new(Crt {
+        cpu: Cpu::new(instructions),
+        screen: Grid::new(SCREEN_WIDTH, SCREEN_HEIGHT, '.'),
+    });
+    driver.run();
+    driver.into_simulation().screen
+}
+
+fn screen_frame(screen: &Grid<char>, state: &SystemState, row: usize, col: usize) -> Frame {
+    // the screen with the beam position marked, and the sprite underneath
+    let mut frame = Frame::new(format!(
+        "cycle {} x={}",
+        state.clock_cycles, state.x_register
+    ));
+    for (y, line) in screen.rows().enumerate() {
+        frame.text(0, y as i64, &line.iter().collect::<String>());
+    }
+    frame.set(col as i64, row as i64, '@');
+    frame.text(state.x_register - 1, SCREEN_HEIGHT as i64 + 1, "###");
+    frame
+}
+
+fn screen(program: &Program) -> Grid<char> {
+    draw_screen(&program.0)
+}
+
+pub fn part_b(program: &Program, decode: bool) -> Result<ReturnType, OcrError> {
+    // the screen shows capital letters, returned as text unless the picture
+    // is asked for
+    let screen = screen(program);
+    if !decode {
+        return Ok(screen.into());
+    }
+    ocr::read(&screen.to_rows()).map(Into::into)
+}
+
+pub fn render(input: &str) -> Image {
+    Image::from_grid(&screen(&parse(input)), |c| {
+        if *c == '#' {
+            GREEN
+        } else {
+            BLACK
+        }
+    })
+}
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = check_not_empty(input).into_iter().collect();
+    for (idx, line) in input.lines().enumerate() {
+        match line.split_once(' ') {
+            _ if line == "noop" => (),
+            Some(("addx", value)) => problems.extend(check_number::<i64>(idx, 5, value)),
+            _ => problems.push(Problem::new(idx, 0, "expected `noop` or `addx <value>`")),
         }
     }
-    "\n".to_owned() + &screen.map(|row| row.iter().collect::<String>()).join("\n")
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(10);
-        assert_eq!(part_a(&input), 13140);
+        assert_eq!(part_a(&parse(&input)), 13140);
+    }
+
+    #[test]
+    fn test_cpu() {
+        let program = parse(&read_test_file(10));
+        let mut driver = Driver::new(Cpu::new(&program.0)).with_snapshots(50);
+        assert!(driver.run_until(|s| s.clock_cycles == 20));
+        assert_eq!(driver.state().x_register, 21);
+        driver.run();
+        driver.rewind_to(220);
+        assert_eq!(driver.state().x_register, 18);
     }
 
     #[test]
-    fn test_part_b() {
-        let expected_output = "\n".to_owned()
-            + &vec![
-                "##..##..##..##..##..##..##..##..##..##..",
-                "###...###...###...###...###...###...###.",
-                "####....####....####....####....####....",
-                "#####.....#####.....#####.....#####.....",
-                "######......######......######......####",
-                "#######.......#######.......#######.....",
-            ]
-            .join("\n");
+    fn test_screen() {
+        let expected_output: Vec<Vec<char>> = [
+            "##..##..##..##..##..##..##..##..##..##..",
+            "###...###...###...###...###...###...###.",
+            "####....####....####....####....####....",
+            "#####.....#####.....#####.....#####.....",
+            "######......######......######......####",
+            "#######.......#######.......#######.....",
+        ]
+        .iter()
+        .map(|row| row.chars().collect())
+        .collect();
 
         let input = read_test_file(10);
-        assert_eq!(part_b(&input), expected_output);
+        assert_eq!(screen(&parse(&input)).to_rows(), expected_output);
+    }
+
+    #[test]
+    fn test_part_b_unreadable_screen() {
+        // the example's screen is a test pattern rather than letters
+        let input = read_test_file(10);
+        assert!(matches!(
+            part_b(&parse(&input), true),
+            Err(OcrError::UnrecognisedGlyph { column: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_part_b_picture() {
+        let program = parse(&read_test_file(10));
+        assert_eq!(part_b(&program, false), Ok(screen(&program).into()));
     }
 }
@@ -1,7 +1,15 @@
-use eval::Expr;
-use std::num::ParseIntError;
+use crate::arithmetic::{self, Overflow};
+use crate::lint::{check_not_empty, check_number, check_prefix, fields, sections, Problem};
+use crate::parser::{
+    delimited, expect_parse, integer, line, literal, map, paragraphs, parse_all, preceded,
+    separated, spaces, value, PResult, ParseError, Parser,
+};
+use crate::simulation::{Driver, Simulation};
+use crate::{alt, explain};
 use std::str::FromStr;
 
+const DAY: u32 = 11;
+
 type MonkeyId = usize;
 type ItemWorry = i64;
 
@@ -10,83 +18,139 @@ const OPERATION_PREFIX: &str = "Operation: new = ";
 const RELIEF_DIVISOR: i64 = 3;
 const NO_RELIEF_DIVISOR: i64 = 1;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operand {
+    Old,
+    Number(ItemWorry),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operation {
+    Add(Operand),
+    Multiply(Operand),
+}
+
+impl Operation {
+    fn apply(self, old: ItemWorry) -> Result<ItemWorry, Overflow> {
+        let value = |operand| match operand {
+            Operand::Old => old,
+            Operand::Number(n) => n,
+        };
+        match self {
+            Operation::Add(operand) => arithmetic::add(DAY, "worry", old, value(operand)),
+            Operation::Multiply(operand) => arithmetic::mul(DAY, "worry", old, value(operand)),
+        }
+    }
+}
+
+fn operation<'a>() -> impl Parser<'a, Operation> {
+    // `old + 6`, `old * 19` or `old * old`
+    let operand = || {
+        alt!(
+            value(literal("old"), Operand::Old),
+            map(integer(), Operand::Number),
+        )
+    };
+    preceded(
+        literal("old "),
+        alt!(
+            map(preceded(literal("+ "), operand()), Operation::Add),
+            map(preceded(literal("* "), operand()), Operation::Multiply),
+        ),
+    )
+}
+
 #[derive(Clone)]
 struct Monkey {
     items: Vec<i64>,
-    operation: String,
+    operation: Operation,
     conditional_divisor: i64,
     true_target: MonkeyId,
     false_target: MonkeyId,
-    total_inspections: i64,
+    total_inspections: u64,
+}
+
+fn monkey(input: &str) -> PResult<'_, Monkey> {
+    // expected input, indentation is optional:
+    // Monkey 0:
+    //   Starting items: 79, 98
+    //   Operation: new = old * 19
+    //   Test: divisible by 23
+    //     If true: throw to monkey 2
+    //     If false: throw to monkey 3
+    let field = |prefix| preceded(spaces(), literal(prefix));
+    let (_, input) = line(delimited(
+        literal("Monkey "),
+        integer::<MonkeyId>(),
+        literal(":"),
+    ))(input)?;
+    let (items, input) = line(preceded(
+        field("Starting items: "),
+        separated(integer::<ItemWorry>(), literal(", ")),
+    ))(input)?;
+    let (operation, input) = line(preceded(field(OPERATION_PREFIX), operation()))(input)?;
+    let (conditional_divisor, input) =
+        line(preceded(field("Test: divisible by "), integer::<i64>()))(input)?;
+    let (true_target, input) = line(preceded(
+        field("If true: throw to monkey "),
+        integer::<MonkeyId>(),
+    ))(input)?;
+    let (false_target, input) = line(preceded(
+        field("If false: throw to monkey "),
+        integer::<MonkeyId>(),
+    ))(input)?;
+
+    let monkey = Monkey {
+        items,
+        operation,
+        conditional_divisor,
+        true_target,
+        false_target,
+        total_inspections: 0,
+    };
+    Ok((monkey, input))
 }
 
 impl FromStr for Monkey {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines().collect::<Vec<&str>>();
-
-        let maybe_items: Result<Vec<ItemWorry>, ParseIntError> = remove_non_numeric_chars(lines[1])
-            .split_whitespace()
-            .map(|s| s.parse::<ItemWorry>())
-            .collect();
-        let operation = lines[2].replace(OPERATION_PREFIX, "");
-        let conditional_divisor: i64 = remove_non_numeric_chars(lines[3]).parse::<i64>()?;
-        let true_target: MonkeyId = remove_non_numeric_chars(lines[4]).parse::<MonkeyId>()?;
-        let false_target: MonkeyId = remove_non_numeric_chars(lines[5]).parse::<MonkeyId>()?;
-
-        Ok(Monkey {
-            items: maybe_items?,
-            operation: operation,
-            conditional_divisor: conditional_divisor,
-            true_target: true_target,
-            false_target: false_target,
-            total_inspections: 0,
-        })
+        parse_all(monkey, s)
     }
 }
 
-fn remove_non_numeric_chars(input: &str) -> String {
-    let filtered_input: String = input
-        .chars()
-        .filter(|c| c.is_numeric() || c.is_whitespace())
-        .collect();
-    filtered_input.trim().to_string()
-}
+// the monkeys as they start, each part plays its rounds on its own copy
+pub struct Monkeys(Vec<Monkey>);
 
-fn parse_monkeys(input: &str) -> Vec<Monkey> {
-    let monkey_strings: Vec<&str> = input.split("\n\n").collect();
-    let monkeys: Vec<Monkey> = monkey_strings
-        .iter()
-        .map(|m| m.parse::<Monkey>().unwrap())
-        .collect();
-    monkeys
+pub fn parse(input: &str) -> Monkeys {
+    Monkeys(expect_parse(paragraphs(monkey), input))
 }
 
-fn calc_divisor_common_demoninator(monkeys: &Vec<Monkey>) -> i64 {
+fn calc_divisor_common_demoninator(monkeys: &[Monkey]) -> Result<i64, Overflow> {
     // this is a hacky workaround for the large numbers we have to deal with
     // in part b of the problem.
-    monkeys
-        .iter()
-        .map(|m| m.conditional_divisor)
-        .fold(1, |a, b| a * b)
+    arithmetic::product(
+        DAY,
+        "common denominator",
+        monkeys.iter().map(|m| m.conditional_divisor),
+    )
 }
 
 fn new_item_worry(
     item: ItemWorry,
-    operation: &str,
+    operation: Operation,
     relief_divisor: i64,
     common_denominator: i64,
-) -> ItemWorry {
-    let expression = Expr::new(operation).value("old", item);
-    let worry_during_inspection = expression.exec().ok().and_then(|v| v.as_i64()).unwrap();
-    (worry_during_inspection / relief_divisor) % common_denominator
+) -> Result<ItemWorry, Overflow> {
+    let worry_during_inspection = operation.apply(item)?;
+    Ok((worry_during_inspection / relief_divisor) % common_denominator)
 }
 
-fn process_round(input: Vec<Monkey>, relief_divisor: i64, common_denominator: i64) -> Vec<Monkey> {
-    // documentation on evaluating the operation string can be found here:
-    // https://docs.rs/eval/latest/eval/
-    let mut monkeys = input.clone();
+fn process_round(
+    monkeys: &mut [Monkey],
+    relief_divisor: i64,
+    common_denominator: i64,
+) -> Result<(), Overflow> {
     for m in 0..monkeys.len() {
         let monkey = &mut monkeys[m];
         let true_target = monkey.true_target;
@@ -96,9 +160,9 @@ fn process_round(input: Vec<Monkey>, relief_divisor: i64, common_denominator: i6
         let items: Vec<ItemWorry> = monkey
             .items
             .drain(..)
-            .map(|i| new_item_worry(i, &monkey.operation, relief_divisor, common_denominator))
-            .collect();
-        monkey.total_inspections += items.len() as i64;
+            .map(|i| new_item_worry(i, monkey.operation, relief_divisor, common_denominator))
+            .collect::<Result<_, _>>()?;
+        monkey.total_inspections += items.len() as u64;
 
         for item in items {
             let target_monkey = if item % conditional_divisor == 0 {
@@ -107,45 +171,202 @@ fn process_round(input: Vec<Monkey>, relief_divisor: i64, common_denominator: i6
                 false_target
             };
             // give the item to the target monkey
+            explain!(
+                Step,
+                "monkey {} throws {} to monkey {}",
+                m,
+                item,
+                target_monkey
+            );
             monkeys[target_monkey].items.push(item);
         }
     }
+    Ok(())
+}
+
+// plays one round of keep away per step. The monkeys never run out of
+// rounds, the troop only stops once a round overflows
+struct Troop {
+    monkeys: Vec<Monkey>,
+    relief_divisor: i64,
+    common_denominator: i64,
+    round: usize,
+    overflow: Option<Overflow>,
+}
 
-    monkeys
+impl Troop {
+    fn new(starting_monkeys: &Monkeys, relief_divisor: i64) -> Result<Troop, Overflow> {
+        Ok(Troop {
+            monkeys: starting_monkeys.0.clone(),
+            relief_divisor,
+            common_denominator: calc_divisor_common_demoninator(&starting_monkeys.0)?,
+            round: 0,
+            overflow: None,
+        })
+    }
 }
 
-pub fn part_a(input: &str) -> i64 {
-    let mut monkeys = parse_monkeys(input);
-    let common_demoninator = calc_divisor_common_demoninator(&monkeys);
+impl Simulation for Troop {
+    type State = Vec<Monkey>;
+    type Snapshot = (Vec<Monkey>, usize);
 
-    for _round in 0..20 {
-        monkeys = process_round(monkeys, RELIEF_DIVISOR, common_demoninator);
+    fn step(&mut self) -> bool {
+        if self.overflow.is_some() {
+            return false;
+        }
+        let round = process_round(
+            &mut self.monkeys,
+            self.relief_divisor,
+            self.common_denominator,
+        );
+        if let Err(overflow) = round {
+            self.overflow = Some(overflow);
+            return false;
+        }
+        self.round += 1;
+        explain!(
+            State,
+            "finished round {}",
+            self.round;
+            items = self.monkeys.iter().map(|m| &m.items).collect::<Vec<_>>()
+        );
+        true
     }
 
-    let mut inspections: Vec<i64> = monkeys.iter().map(|m| m.total_inspections).collect();
-    inspections.sort();
-    inspections.iter().rev().take(2).product::<i64>()
-}
+    fn state(&self) -> &Vec<Monkey> {
+        &self.monkeys
+    }
 
-pub fn part_b(input: &str) -> i64 {
-    let mut monkeys = parse_monkeys(input);
-    let common_demoninator = calc_divisor_common_demoninator(&monkeys);
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.monkeys.clone(), self.round)
+    }
 
-    for _round in 0..10_000 {
-        monkeys = process_round(monkeys, NO_RELIEF_DIVISOR, common_demoninator);
+    fn restore(&mut self, (monkeys, round): &Self::Snapshot) {
+        self.monkeys = monkeys.clone();
+        self.round = *round;
+        self.overflow = None;
+    }
+}
+
+fn monkey_business(
+    starting_monkeys: &Monkeys,
+    relief_divisor: i64,
+    rounds: usize,
+) -> Result<u64, Overflow> {
+    let mut driver = Driver::new(Troop::new(starting_monkeys, relief_divisor)?);
+    if !driver.run_to(rounds) {
+        return Err(driver.into_simulation().overflow.unwrap());
     }
 
-    let mut inspections: Vec<i64> = monkeys.iter().map(|m| m.total_inspections).collect();
+    let mut inspections: Vec<u64> = driver.state().iter().map(|m| m.total_inspections).collect();
     inspections.sort();
-    inspections.iter().rev().take(2).product::<i64>()
+    arithmetic::product(
+        DAY,
+        "monkey business",
+        inspections.iter().rev().take(2).copied(),
+    )
+}
+
+pub fn part_a(starting_monkeys: &Monkeys) -> Result<u64, Overflow> {
+    monkey_business(starting_monkeys, RELIEF_DIVISOR, 20)
+}
+
+pub fn part_b(starting_monkeys: &Monkeys) -> Result<u64, Overflow> {
+    monkey_business(starting_monkeys, NO_RELIEF_DIVISOR, 10_000)
+}
+
+// each monkey is described by these lines, in this order
+const MONKEY_LINES: [&str; 6] = [
+    "Monkey ",
+    "Starting items: ",
+    OPERATION_PREFIX,
+    "Test: divisible by ",
+    "If true: throw to monkey ",
+    "If false: throw to monkey ",
+];
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    if let Some(problem) = check_not_empty(input) {
+        return vec![problem];
+    }
+    let monkeys = sections(input);
+    let mut problems = Vec::new();
+    let mut targets: Vec<(usize, usize, usize)> = Vec::new();
+    for (n, (start, lines)) in monkeys.iter().enumerate() {
+        if lines.len() != MONKEY_LINES.len() {
+            problems.push(Problem::new(
+                start + lines.len().min(MONKEY_LINES.len()),
+                0,
+                format!(
+                    "monkey has {} lines, expected {}",
+                    lines.len(),
+                    MONKEY_LINES.len()
+                ),
+            ));
+        }
+        for (offset, (line, prefix)) in lines.iter().zip(MONKEY_LINES).enumerate() {
+            let idx = start + offset;
+            let text = line.trim_start();
+            let indent = line.len() - text.len();
+            if let Some(mut problem) = check_prefix(idx, text, prefix) {
+                problem.column += indent;
+                problems.push(problem);
+                continue;
+            }
+            let column = indent + prefix.len();
+            let value = &text[prefix.len()..];
+            match offset {
+                0 if value != format!("{}:", n) => problems.push(Problem::new(
+                    idx,
+                    column,
+                    format!("expected `{}:`, monkeys must be numbered in order", n),
+                )),
+                1 => {
+                    let mut item_column = column;
+                    for item in value.split(", ") {
+                        problems.extend(check_number::<ItemWorry>(idx, item_column, item));
+                        item_column += item.len() + 2;
+                    }
+                }
+                2 => match fields(value).as_slice() {
+                    [(_, "old"), (_, "+" | "*"), (_, "old")] => (),
+                    [(_, "old"), (_, "+" | "*"), (operand_column, operand)] => {
+                        problems.extend(check_number::<i64>(idx, column + operand_column, operand))
+                    }
+                    _ => problems.push(Problem::new(
+                        idx,
+                        column,
+                        "expected an operation like `old * 19` or `old + old`",
+                    )),
+                },
+                3 => problems.extend(check_number::<i64>(idx, column, value)),
+                4 | 5 => match value.parse::<MonkeyId>() {
+                    Ok(target) => targets.push((idx, column, target)),
+                    Err(_) => problems.extend(check_number::<MonkeyId>(idx, column, value)),
+                },
+                _ => (),
+            }
+        }
+    }
+    for (idx, column, target) in targets {
+        if target >= monkeys.len() {
+            problems.push(Problem::new(
+                idx,
+                column,
+                format!("there is no monkey {}", target),
+            ));
+        }
+    }
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_create_monkey_from_str() {
         let input = vec![
             "Monkey 0:",
@@ -158,6 +379,7 @@ mod tests {
         .join("\n");
         let monkey = input.parse::<Monkey>().unwrap();
         assert_eq!(monkey.items, vec![99, 67, 92, 61, 83, 64, 98]);
+        assert_eq!(monkey.operation, Operation::Multiply(Operand::Number(17)));
         assert_eq!(monkey.conditional_divisor, 3);
         assert_eq!(monkey.true_target, 4);
         assert_eq!(monkey.false_target, 2);
@@ -166,13 +388,39 @@ mod tests {
     #[test]
     fn test_part_a() {
         let input = read_test_file(11);
-        assert_eq!(part_a(&input), 10605);
+        assert_eq!(part_a(&parse(&input)), Ok(10605));
+    }
+
+    #[test]
+    fn test_rewind() {
+        let monkeys = parse(&read_test_file(11));
+        let troop = Troop::new(&monkeys, RELIEF_DIVISOR).unwrap();
+        let mut driver = Driver::new(troop).with_snapshots(5);
+        driver.run_to(20);
+        driver.rewind_to(1);
+        assert_eq!(driver.state()[0].items, vec![20, 23, 27, 26]);
+        assert_eq!(driver.state()[3].total_inspections, 5);
     }
 
     #[test]
-    #[ignore] // ignored as takes 5s. Run with `cargo test -- --ignored`
     fn test_part_b() {
         let input = read_test_file(11);
-        assert_eq!(part_b(&input), 2713310158);
+        assert_eq!(part_b(&parse(&input)), Ok(2713310158));
+    }
+
+    #[test]
+    fn test_lint() {
+        let input = read_test_file(11);
+        assert_eq!(lint(&input), vec![]);
+
+        let mangled = input.replacen(
+            "If false: throw to monkey 3",
+            "If false: throw to monkey 9",
+            1,
+        );
+        assert_eq!(
+            lint(&mangled),
+            vec![Problem::new(5, 30, "there is no monkey 9")]
+        );
     }
 }
@@ -1,29 +1,14 @@
+use crate::animation::{self, Frame};
+use crate::explain;
+use crate::grid::{Grid, Point};
+use crate::lint::{check_grid, Problem};
+use crate::render::{gradient, Image, Rgb, BLUE, GOLD, RED, WHITE};
+use crate::search::{self, Cost};
 use itertools::Itertools;
-use petgraph::algo::dijkstra;
-use petgraph::graph::{DiGraph, NodeIndex};
-
-// bit filthy hard coding these, but it's probably fine and makes
-// searching for the start / end points a bit easier
-const SOURCE_ID: u32 = 0;
-const TARGET_ID: u32 = 9_999;
 
 const SOURCE_HEIGHT: u32 = 0;
 const TARGET_HEIGHT: u32 = 25;
 
-#[derive(Clone)]
-struct Node {
-    id: u32,
-    height: u32,
-}
-
-fn id(c: char, row: usize, col: usize, line_length: usize) -> u32 {
-    match c {
-        'S' => SOURCE_ID,
-        'E' => TARGET_ID,
-        _ => ((row * line_length) + col + 1) as u32,
-    }
-}
-
 fn height(c: char) -> u32 {
     match c {
         'S' => SOURCE_HEIGHT,
@@ -32,123 +17,185 @@ fn height(c: char) -> u32 {
     }
 }
 
-fn line_to_vec(line: &str, row: usize) -> Vec<Node> {
-    line.chars()
-        .enumerate()
-        .map(|(idx, c)| Node {
-            id: id(c, row, idx, line.len()),
-            height: height(c),
-        })
-        .collect()
+// the map as read, the height of each square and where the climb starts and ends
+pub struct HeightMap {
+    chars: Grid<char>,
+    heights: Grid<u32>,
+    source: Point,
+    target: Point,
 }
 
-fn find_edges_for_coordinate(
-    grid: &Vec<Vec<Node>>,
-    row_idx: usize,
-    col_idx: usize,
-) -> Vec<(u32, u32)> {
-    let source = &grid[row_idx as usize][col_idx as usize];
-
-    let row = row_idx as i32;
-    let col = col_idx as i32;
-
-    let raw_search_coords = vec![
-        (row - 1, col),
-        (row + 1, col),
-        (row, col - 1),
-        (row, col + 1),
-    ];
-
-    // only keep coords that are in bounds
-    let filtered_search_coords: Vec<(i32, i32)> = raw_search_coords
-        .into_iter()
-        .filter(|(r, c)| *r >= 0 && *r < grid.len() as i32 && *c >= 0 && *c < grid[0].len() as i32)
-        .collect();
-
-    // get the edges where there is a valid path
-    let edges: Vec<(u32, u32)> = filtered_search_coords
-        .into_iter()
-        .filter_map(|(r, c)| {
-            let target = &grid[r as usize][c as usize];
-            if (target.height as i32 - source.height as i32) < 2 {
-                Some((source.id, target.id))
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    edges
+pub fn parse(input: &str) -> HeightMap {
+    let chars: Grid<char> = input.parse().unwrap();
+    let find = |marker| {
+        chars
+            .iter()
+            .find(|(_, c)| **c == marker)
+            .map(|(p, _)| p)
+            .unwrap_or_else(|| panic!("no `{}` on the map", marker))
+    };
+    HeightMap {
+        heights: chars.map_points(|_, c| height(*c)),
+        source: find('S'),
+        target: find('E'),
+        chars,
+    }
+}
+
+fn can_step(source: u32, target: u32) -> bool {
+    // at most one step up, but any distance down
+    (target as i32 - source as i32) < 2
 }
 
-fn build_graph(grid: Vec<Vec<Node>>) -> petgraph::Graph<i32, ()> {
-    let mut edges: Vec<(u32, u32)> = Vec::new();
-    for (row_idx, rows) in grid.iter().enumerate() {
-        for (col_idx, _) in rows.iter().enumerate() {
-            edges.append(&mut find_edges_for_coordinate(&grid, row_idx, col_idx));
+fn steps(map: &HeightMap, point: Point) -> impl Iterator<Item = Point> + '_ {
+    map.heights
+        .neighbours4(point)
+        .filter(move |p| can_step(map.heights[point], map.heights[*p]))
+}
+
+fn animate_search(map: &HeightMap) {
+    // a breadth first search from the source, one frame per step: visited
+    // squares are `.` and the frontier is `@`
+    let search = search::bfs(map.source, |p| steps(map, *p), |_| false);
+    let mut layers: Vec<Vec<Point>> = Vec::new();
+    for (point, cost) in search.visited() {
+        let cost = cost as usize;
+        if layers.len() <= cost {
+            layers.resize(cost + 1, Vec::new());
+        }
+        layers[cost].push(*point);
+    }
+
+    for (step, frontier) in layers.iter().enumerate() {
+        let mut frame = Frame::new(format!("step {}, frontier {}", step, frontier.len()));
+        for (y, row) in map.chars.rows().enumerate() {
+            frame.text(0, y as i64, &row.iter().collect::<String>());
+        }
+        for (x, y) in layers[..step].iter().flatten() {
+            frame.set(*x as i64, *y as i64, '.');
+        }
+        for (x, y) in frontier {
+            frame.set(*x as i64, *y as i64, '@');
         }
+        animation::push(frame);
     }
-    let unique_edges: Vec<(u32, u32)> = edges.into_iter().unique().collect();
-    let g = DiGraph::<i32, ()>::from_edges(&unique_edges);
-    g
 }
 
-pub fn part_a(input: &str) -> u32 {
-    let grid: Vec<Vec<Node>> = input
-        .lines()
-        .enumerate()
-        .map(|(row, l)| line_to_vec(l, row))
-        .collect();
+pub fn part_a(map: &HeightMap) -> u32 {
+    if animation::enabled() {
+        animate_search(map);
+    }
 
-    let graph = build_graph(grid);
+    let search = search::bfs(map.source, |p| steps(map, *p), |p| *p == map.target);
+    search.goal_cost().unwrap().try_into().unwrap()
+}
 
-    let node_map = dijkstra(&graph, SOURCE_ID.into(), None, |_| 1);
-    *node_map.get(&NodeIndex::new(TARGET_ID as usize)).unwrap()
+pub fn part_b(map: &HeightMap) -> u32 {
+    // one search from every square at height 0 at once finds the nearest of them
+    let starts = map
+        .heights
+        .iter()
+        .filter(|(_, height)| **height == SOURCE_HEIGHT)
+        .map(|(p, _)| p);
+    let search = search::multi_bfs(starts, |p| steps(map, *p), |p| *p == map.target);
+    let steps = search.goal_cost().unwrap();
+    // the path is only walked back when explaining
+    explain!(
+        Decision,
+        "nearest start is {:?}, {} steps away",
+        search.path().unwrap()[0],
+        steps
+    );
+    steps.try_into().unwrap()
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let grid: Vec<Vec<Node>> = input
-        .lines()
-        .enumerate()
-        .map(|(row, l)| line_to_vec(l, row))
-        .collect();
-
-    let graph = build_graph(grid.clone());
-
-    let mut candidate_shortest_distances: Vec<Option<u32>> = Vec::new();
-
-    // brute force djiikstra for each node at height 0
-    // this isn't that efficient, but solves the problem
-    for rows in grid.iter() {
-        for node in rows.iter() {
-            if node.height == 0 {
-                let target = NodeIndex::new(TARGET_ID as usize);
-                let node_map = dijkstra(&graph, node.id.into(), Some(target), |_| 1);
-                let distance = node_map
-                    .get(&NodeIndex::new(TARGET_ID as usize))
-                    .map(|x| *x);
-                candidate_shortest_distances.push(distance);
-            }
+pub fn render(input: &str) -> Image {
+    // height map from dark green valleys to white peaks, with the shortest
+    // path in blue, the start in red and the end in gold
+    let map = parse(input);
+
+    let mut image = Image::from_grid(&map.chars, |c| match c {
+        'S' => RED,
+        'E' => GOLD,
+        _ => gradient(height(*c), TARGET_HEIGHT, Rgb(0, 80, 0), WHITE),
+    });
+
+    let (tx, ty) = map.target;
+    let manhattan = |&(x, y): &Point| (x.abs_diff(tx) + y.abs_diff(ty)) as Cost;
+    let search = search::astar(
+        map.source,
+        |p| steps(&map, *p).map(|p| (p, 1)),
+        manhattan,
+        |p| *p == map.target,
+    );
+    for (x, y) in search.path().unwrap_or_default() {
+        if (x, y) != map.source && (x, y) != map.target {
+            image.set(x, y, BLUE);
         }
     }
+    image
+}
 
-    *candidate_shortest_distances.iter().flatten().min().unwrap()
+pub fn lint(input: &str) -> Vec<Problem> {
+    let mut problems = check_grid(
+        input,
+        |c| c.is_ascii_lowercase() || c == 'S' || c == 'E',
+        "a height `a`-`z`, `S` or `E`",
+    );
+    for (marker, name) in [('S', "start"), ('E', "end")] {
+        let found: Vec<(usize, usize)> = input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .positions(move |c| c == marker)
+                    .map(move |x| (y, x))
+            })
+            .collect();
+        match found.as_slice() {
+            [] => problems.push(Problem::new(
+                0,
+                0,
+                format!("no {} `{}` found", name, marker),
+            )),
+            [_] => (),
+            [_, extra @ ..] => problems.extend(extra.iter().map(|(y, x)| {
+                Problem::new(*y, *x, format!("more than one {} `{}`", name, marker))
+            })),
+        }
+    }
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(12);
-        assert_eq!(part_a(&input), 31);
+        assert_eq!(part_a(&parse(&input)), 31);
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(12);
-        assert_eq!(part_b(&input), 29);
+        assert_eq!(part_b(&parse(&input)), 29);
+    }
+
+    #[test]
+    fn test_lint() {
+        let input = read_test_file(12);
+        assert_eq!(lint(&input), vec![]);
+
+        let mangled = input.replace('E', "e").replace("abcryxxl", "abcrySxl");
+        assert_eq!(
+            lint(&mangled),
+            vec![
+                Problem::new(1, 5, "more than one start `S`"),
+                Problem::new(0, 0, "no end `E` found"),
+            ]
+        );
     }
 }
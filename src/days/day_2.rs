@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use crate::lint::{check_not_empty, Problem};
+use crate::parser::{expect_parse, lines, literal, map, one_of, separated_pair, Parser};
+use crate::stream::{for_each_line, parse_line};
+use std::io::BufRead;
 use DesiredOutcome::{Draw, Loss, Win};
 use Selection::{Paper, Rock, Scissors};
 
@@ -29,37 +32,34 @@ impl Selection {
     }
 }
 
-impl FromStr for Selection {
-    type Err = ();
+impl TryFrom<char> for Selection {
+    type Error = ();
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "A" => Ok(Rock),
-            "B" => Ok(Paper),
-            "C" => Ok(Scissors),
-            "X" => Ok(Rock),
-            "Y" => Ok(Paper),
-            "Z" => Ok(Scissors),
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'A' | 'X' => Ok(Rock),
+            'B' | 'Y' => Ok(Paper),
+            'C' | 'Z' => Ok(Scissors),
             _ => Err(()),
         }
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Copy, Clone, PartialEq)]
 enum DesiredOutcome {
     Win,
     Loss,
     Draw,
 }
 
-impl FromStr for DesiredOutcome {
-    type Err = ();
+impl TryFrom<char> for DesiredOutcome {
+    type Error = ();
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "X" => Ok(Loss),
-            "Y" => Ok(Draw),
-            "Z" => Ok(Win),
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'X' => Ok(Loss),
+            'Y' => Ok(Draw),
+            'Z' => Ok(Win),
             _ => Err(()),
         }
     }
@@ -98,50 +98,79 @@ fn determine_selection(opponent: Selection, outcome: DesiredOutcome) -> Selectio
     }
 }
 
-pub fn part_a(input: &str) -> u32 {
-    let games = input.lines().collect::<Vec<&str>>();
-
-    let game_scores: Vec<u32> = games
-        .iter()
-        .map(|x| {
-            let player_str = &x[x.len() - 1..];
-            let opponent_str = &x[..1];
-
-            let player_selection = Selection::from_str(player_str).unwrap();
-            let opponent_selection = Selection::from_str(opponent_str).unwrap();
+struct Round {
+    opponent: Selection,
+    // the second column is read as a selection in part a and an outcome in part b
+    player: Selection,
+    outcome: DesiredOutcome,
+}
 
-            score_game(player_selection, opponent_selection)
-        })
-        .collect();
+fn round<'a>() -> impl Parser<'a, Round> {
+    // e.g. `A Y`, the parser only lets through letters these conversions accept
+    map(
+        separated_pair(one_of("ABC"), literal(" "), one_of("XYZ")),
+        |(opponent, second_column)| Round {
+            opponent: Selection::try_from(opponent).unwrap(),
+            player: Selection::try_from(second_column).unwrap(),
+            outcome: DesiredOutcome::try_from(second_column).unwrap(),
+        },
+    )
+}
 
-    game_scores.iter().sum()
+fn score_round(part: char, round: &Round) -> u32 {
+    match part {
+        'a' => score_game(round.player, round.opponent),
+        _ => {
+            let player_selection = determine_selection(round.opponent, round.outcome);
+            score_game(player_selection, round.opponent)
+        }
+    }
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let games = input.lines().collect::<Vec<&str>>();
+pub struct StrategyGuide(Vec<Round>);
 
-    let game_scores: Vec<u32> = games
-        .iter()
-        .map(|x| {
-            let outcome_str = &x[x.len() - 1..];
-            let opponent_str = &x[..1];
+pub fn parse(input: &str) -> StrategyGuide {
+    StrategyGuide(expect_parse(lines(round()), input))
+}
 
-            let outcome = DesiredOutcome::from_str(outcome_str).unwrap();
-            let opponent_selection = Selection::from_str(opponent_str).unwrap();
+pub fn part_a(guide: &StrategyGuide) -> u32 {
+    guide.0.iter().map(|round| score_round('a', round)).sum()
+}
 
-            let player_selection = determine_selection(opponent_selection, outcome);
+pub fn part_b(guide: &StrategyGuide) -> u32 {
+    guide.0.iter().map(|round| score_round('b', round)).sum()
+}
 
-            score_game(player_selection, opponent_selection)
-        })
-        .collect();
+pub fn solve_reader(part: char, reader: impl BufRead) -> u32 {
+    let mut total = 0;
+    for_each_line(reader, |idx, line| {
+        total += score_round(part, &parse_line(round(), idx, line));
+    });
+    total
+}
 
-    game_scores.iter().sum()
+pub fn lint(input: &str) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = check_not_empty(input).into_iter().collect();
+    for (idx, line) in input.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.len() != 3 || chars[1] != ' ' {
+            problems.push(Problem::new(idx, 0, "expected a round like `A Y`"));
+            continue;
+        }
+        if !"ABC".contains(chars[0]) {
+            problems.push(Problem::new(idx, 0, "expected `A`, `B` or `C`"));
+        }
+        if !"XYZ".contains(chars[2]) {
+            problems.push(Problem::new(idx, 2, "expected `X`, `Y` or `Z`"));
+        }
+    }
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_score_game_win() {
@@ -174,12 +203,19 @@ mod tests {
     #[test]
     fn test_part_a() {
         let input = read_test_file(2);
-        assert_eq!(part_a(&input), 15);
+        assert_eq!(part_a(&parse(&input)), 15);
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(2);
-        assert_eq!(part_b(&input), 12);
+        assert_eq!(part_b(&parse(&input)), 12);
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let input = read_test_file(2);
+        assert_eq!(solve_reader('a', input.as_bytes()), 15);
+        assert_eq!(solve_reader('b', input.as_bytes()), 12);
     }
 }
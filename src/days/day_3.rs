@@ -1,3 +1,8 @@
+use crate::lint::{check_not_empty, Problem};
+use crate::parser::{expect_parse, lines, take_while1, Parser};
+use crate::stream::{for_each_line, parse_line};
+use std::io::BufRead;
+
 fn char_priority(c: char) -> u32 {
     if c.is_lowercase() {
         c as u32 - 'a' as u32 + 1
@@ -53,21 +58,31 @@ fn get_priority_for_rucksack_group(rucksacks: Vec<&str>) -> u32 {
     panic!("Did not find priority that was encountered in all three vectors")
 }
 
-pub fn part_a(input: &str) -> u32 {
-    let rucksacks: Vec<&str> = input.lines().collect();
+pub struct Rucksacks(Vec<String>);
+
+fn rucksack<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(|c| c.is_ascii_alphabetic(), "an item letter")
+}
+
+pub fn parse(input: &str) -> Rucksacks {
+    let rucksacks: Vec<&str> = expect_parse(lines(rucksack()), input);
+    Rucksacks(rucksacks.into_iter().map(|r| r.to_string()).collect())
+}
+
+pub fn part_a(rucksacks: &Rucksacks) -> u32 {
     let priorities: Vec<u32> = rucksacks
+        .0
         .iter()
         .map(|r| get_duplicated_priority_for_rucksack(r))
         .collect();
     priorities.iter().sum()
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let rucksacks: Vec<&str> = input.lines().collect();
-
+pub fn part_b(rucksacks: &Rucksacks) -> u32 {
     let grouped_rucksacks: Vec<Vec<&str>> = rucksacks
+        .0
         .chunks_exact(3)
-        .map(|chunk| chunk.to_vec())
+        .map(|chunk| chunk.iter().map(|r| r.as_str()).collect())
         .collect();
 
     let priorities = grouped_rucksacks
@@ -77,20 +92,75 @@ pub fn part_b(input: &str) -> u32 {
     priorities.sum()
 }
 
+pub fn solve_reader(part: char, reader: impl BufRead) -> u32 {
+    // part b only holds on to the current group of three
+    let mut total = 0;
+    let mut group: Vec<String> = Vec::with_capacity(3);
+    for_each_line(reader, |idx, line| {
+        let rucksack = parse_line(rucksack(), idx, line);
+        if part == 'a' {
+            total += get_duplicated_priority_for_rucksack(rucksack);
+            return;
+        }
+        group.push(rucksack.to_string());
+        if group.len() == 3 {
+            total += get_priority_for_rucksack_group(group.iter().map(|r| r.as_str()).collect());
+            group.clear();
+        }
+    });
+    total
+}
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = check_not_empty(input).into_iter().collect();
+    let mut line_count: usize = 0;
+    for (idx, line) in input.lines().enumerate() {
+        line_count += 1;
+        if let Some(x) = line.chars().position(|c| !c.is_ascii_alphabetic()) {
+            problems.push(Problem::new(idx, x, "expected an item letter"));
+        }
+        if !line.len().is_multiple_of(2) {
+            problems.push(Problem::new(
+                idx,
+                line.len(),
+                format!("{} items can't be split into two compartments", line.len()),
+            ));
+        }
+    }
+    if !line_count.is_multiple_of(3) {
+        problems.push(Problem::new(
+            line_count,
+            0,
+            format!(
+                "{} rucksacks can't be split into groups of three",
+                line_count
+            ),
+        ));
+    }
+    problems
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(3);
-        assert_eq!(part_a(&input), 157);
+        assert_eq!(part_a(&parse(&input)), 157);
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(3);
-        assert_eq!(part_b(&input), 70);
+        assert_eq!(part_b(&parse(&input)), 70);
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let input = read_test_file(3);
+        assert_eq!(solve_reader('a', input.as_bytes()), 157);
+        assert_eq!(solve_reader('b', input.as_bytes()), 70);
     }
 }
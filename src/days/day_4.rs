@@ -1,4 +1,7 @@
-use advent_of_code_2022::to_u32;
+use crate::lint::{check_not_empty, check_number, Problem};
+use crate::parser::{expect_parse, integer, lines, literal, map, separated_pair, Parser};
+use crate::stream::{for_each_line, parse_line};
+use std::io::BufRead;
 
 struct Range {
     lower: u32,
@@ -17,49 +20,92 @@ fn ranges_intersect(a: &Range, b: &Range) -> bool {
     a_lower_within_b || a_upper_within_b || ranges_entirely_overlap(a, b)
 }
 
-fn to_ranges(line: &str) -> (Range, Range) {
-    let cleaned_line: String = line.replace(",", "-");
-    let split_line: Vec<&str> = cleaned_line.split("-").collect();
-    let int_vals: Vec<u32> = split_line.iter().map(|x| to_u32(*x)).collect();
+fn range<'a>() -> impl Parser<'a, Range> {
+    // e.g. `2-4`
+    map(
+        separated_pair(integer(), literal("-"), integer()),
+        |(lower, upper)| Range { lower, upper },
+    )
+}
 
-    let a = Range {
-        lower: int_vals[0],
-        upper: int_vals[1],
-    };
-    let b = Range {
-        lower: int_vals[2],
-        upper: int_vals[3],
-    };
+fn pair_of_ranges<'a>() -> impl Parser<'a, (Range, Range)> {
+    // e.g. `2-4,6-8`
+    separated_pair(range(), literal(","), range())
+}
+
+pub struct Assignments(Vec<(Range, Range)>);
 
-    (a, b)
+pub fn parse(input: &str) -> Assignments {
+    Assignments(expect_parse(lines(pair_of_ranges()), input))
 }
 
-pub fn part_a(input: &str) -> u32 {
-    let lines: Vec<&str> = input.lines().collect();
-    let ranges: Vec<(Range, Range)> = lines.iter().map(|x| to_ranges(x)).collect();
-    let ranges_overlap: Vec<bool> = ranges
+pub fn part_a(assignments: &Assignments) -> u32 {
+    let ranges_overlap: Vec<bool> = assignments
+        .0
         .iter()
         .map(|(a, b)| ranges_entirely_overlap(a, b))
         .collect();
     ranges_overlap.iter().map(|x| if *x { 1 } else { 0 }).sum()
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let lines: Vec<&str> = input.lines().collect();
-    let ranges: Vec<(Range, Range)> = lines.iter().map(|x| to_ranges(x)).collect();
-    let ranges_intersect: Vec<bool> = ranges.iter().map(|(a, b)| ranges_intersect(a, b)).collect();
+pub fn part_b(assignments: &Assignments) -> u32 {
+    let ranges_intersect: Vec<bool> = assignments
+        .0
+        .iter()
+        .map(|(a, b)| ranges_intersect(a, b))
+        .collect();
     ranges_intersect
         .iter()
         .map(|x| if *x { 1 } else { 0 })
         .sum()
 }
 
+pub fn solve_reader(part: char, reader: impl BufRead) -> u32 {
+    let matches = match part {
+        'a' => ranges_entirely_overlap,
+        _ => ranges_intersect,
+    };
+    let mut total = 0;
+    for_each_line(reader, |idx, line| {
+        let (a, b) = parse_line(pair_of_ranges(), idx, line);
+        if matches(&a, &b) {
+            total += 1;
+        }
+    });
+    total
+}
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    // e.g. `2-4,6-8`
+    let mut problems: Vec<Problem> = check_not_empty(input).into_iter().collect();
+    for (idx, line) in input.lines().enumerate() {
+        let ranges: Vec<&str> = line.split(',').collect();
+        if ranges.len() != 2 {
+            problems.push(Problem::new(idx, 0, "expected two ranges like `2-4,6-8`"));
+            continue;
+        }
+        let mut column = 0;
+        for range in ranges {
+            match range.split_once('-') {
+                Some((lower, upper)) => {
+                    problems.extend(check_number::<u32>(idx, column, lower));
+                    problems.extend(check_number::<u32>(idx, column + lower.len() + 1, upper));
+                }
+                None => problems.push(Problem::new(idx, column, "expected a range like `2-4`")),
+            }
+            column += range.len() + 1;
+        }
+    }
+    problems
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_ranges_overlap() {
         let a = Range { lower: 1, upper: 2 };
         let b = Range { lower: 2, upper: 5 };
@@ -70,6 +116,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_ranges_intersect() {
         let a = Range { lower: 1, upper: 2 };
         let b = Range { lower: 2, upper: 5 };
@@ -81,12 +128,19 @@ mod tests {
     #[test]
     fn test_part_a() {
         let input = read_test_file(4);
-        assert_eq!(part_a(&input), 2);
+        assert_eq!(part_a(&parse(&input)), 2);
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(4);
-        assert_eq!(part_b(&input), 4);
+        assert_eq!(part_b(&parse(&input)), 4);
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let input = read_test_file(4);
+        assert_eq!(solve_reader('a', input.as_bytes()), 2);
+        assert_eq!(solve_reader('b', input.as_bytes()), 4);
     }
 }
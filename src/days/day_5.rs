@@ -1,4 +1,11 @@
-use advent_of_code_2022::to_u32;
+use crate::animation::Frame;
+use crate::lint::{check_number, fields, Problem};
+use crate::parser::{
+    delimited, expect_parse, integer, line, lines, literal, many, map, pair, preceded, satisfy,
+    section, separated, spaces, terminated, value, PResult, Parser,
+};
+use crate::simulation::{Driver, Simulation};
+use crate::{alt, explain};
 
 struct Instruction {
     quantity: u32,
@@ -6,12 +13,23 @@ struct Instruction {
     destination: u32,
 }
 
-fn get_stack_width(stack_labels: &str) -> u32 {
-    let last_stack_label = stack_labels.split_whitespace().last().unwrap();
-    to_u32(last_stack_label)
+fn crate_row<'a>() -> impl Parser<'a, Vec<Option<char>>> {
+    // `[A]` for a crate, three spaces where a stack has none at this height
+    let crate_cell = alt!(
+        map(
+            delimited(
+                literal("["),
+                satisfy(|c| c.is_ascii_uppercase(), "a crate letter"),
+                literal("]")
+            ),
+            Some
+        ),
+        value(literal("   "), None)
+    );
+    terminated(separated(crate_cell, literal(" ")), spaces())
 }
 
-fn build_stacks(stack_str: &str) -> Vec<Vec<char>> {
+fn build_stacks(input: &str) -> PResult<'_, Vec<Vec<char>>> {
     // expected input:
     //     [G]         [P]         [M]
     //     [V]     [M] [W] [S]     [Q]
@@ -22,113 +40,280 @@ fn build_stacks(stack_str: &str) -> Vec<Vec<char>> {
     // [G] [M] [Q] [S] [Z] [T] [J] [D] [S]
     // [B] [T] [M] [B] [J] [C] [T] [G] [N]
     //  1   2   3   4   5   6   7   8   9
+    let (rows, rest) = many(line(crate_row()))(input)?;
+    let (labels, rest) = delimited(
+        spaces(),
+        separated(integer::<u32>(), literal("   ")),
+        spaces(),
+    )(rest)?;
 
-    let mut stack_lines: Vec<&str> = stack_str.lines().collect();
-
-    let stack_labels = stack_lines.pop().unwrap();
-    let stack_width = get_stack_width(stack_labels);
-
-    let mut stacks: Vec<Vec<char>> = Vec::with_capacity(stack_width as usize);
-    for _ in 0..stack_width {
-        stacks.push(Vec::new());
+    let mut stacks: Vec<Vec<char>> = vec![Vec::new(); labels.len()];
+    for row in rows.iter().rev() {
+        for (current_stack_idx, cell) in row.iter().enumerate() {
+            if let Some(c) = cell {
+                stacks[current_stack_idx].push(*c);
+            }
+        }
     }
+    Ok((stacks, rest))
+}
 
-    let start_offset: usize = 1;
-    let offset: usize = 4;
-
-    for line in stack_lines.iter().rev() {
-        let chars: Vec<char> = line.chars().collect();
-        let mut current_stack_idx: usize = 0;
+fn instruction<'a>() -> impl Parser<'a, Instruction> {
+    // e.g. `move 2 from 4 to 2`
+    map(
+        pair(
+            preceded(literal("move "), integer()),
+            pair(
+                preceded(literal(" from "), integer()),
+                preceded(literal(" to "), integer()),
+            ),
+        ),
+        |(quantity, (source, destination))| Instruction {
+            quantity,
+            source,
+            destination,
+        },
+    )
+}
 
-        for line_idx in (start_offset..chars.len()).step_by(offset) {
-            let current_char = chars[line_idx];
-            if current_char != ' ' {
-                stacks[current_stack_idx].push(current_char)
-            }
-            current_stack_idx += 1;
+fn stacks_frame(stacks: &[Vec<char>], caption: String) -> Frame {
+    // draws the stacks the same way the puzzle input does, crates at the bottom
+    let mut frame = Frame::new(caption);
+    let max_height = stacks.iter().map(|s| s.len()).max().unwrap_or(0) as i64;
+    for (stack_idx, stack) in stacks.iter().enumerate() {
+        let x = stack_idx as i64 * 4;
+        for (level, c) in stack.iter().enumerate() {
+            frame.text(x, max_height - 1 - level as i64, &format!("[{}]", c));
         }
+        frame.text(x + 1, max_height, &(stack_idx + 1).to_string());
     }
-    stacks
+    frame
 }
 
-fn build_instruction(line: &str) -> Instruction {
-    let cleaned_line: String = line
-        .replace("move", " ")
-        .replace("from", " ")
-        .replace("to", " ");
+fn read_top_of_stacks(stacks: &[Vec<char>]) -> String {
+    stacks.iter().map(|s| s[s.len() - 1]).collect()
+}
 
-    let line_content: Vec<u32> = cleaned_line.split_whitespace().map(|x| to_u32(x)).collect();
+// the starting stacks and the moves to make, each part works on its own copy
+// of the stacks
+pub struct Procedure {
+    stacks: Vec<Vec<char>>,
+    instructions: Vec<Instruction>,
+}
 
-    Instruction {
-        quantity: line_content[0],
-        source: line_content[1],
-        destination: line_content[2],
+pub fn parse(input: &str) -> Procedure {
+    let (stacks, instructions) =
+        expect_parse(pair(section(build_stacks), lines(instruction())), input);
+    Procedure {
+        stacks,
+        instructions,
     }
 }
 
-fn build_instructions(instructions: &str) -> Vec<Instruction> {
-    // expected input:
-    // move 2 from 4 to 2
-    // move 6 from 9 to 7
-    // move 4 from 7 to 2
-    // move 2 from 4 to 1
-    // move 2 from 6 to 7
-    instructions.lines().map(|l| build_instruction(l)).collect()
+// the crane works through the instructions one per step
+struct Crane<'a> {
+    stacks: Vec<Vec<char>>,
+    instructions: &'a [Instruction],
+    next: usize,
+    // the CrateMover 9001 of part b lifts several crates at once
+    all_at_once: bool,
 }
 
-fn read_top_of_stacks(stacks: &Vec<Vec<char>>) -> String {
-    stacks.iter().map(|s| s[s.len() - 1]).into_iter().collect()
-}
+impl<'a> Crane<'a> {
+    fn new(procedure: &'a Procedure, all_at_once: bool) -> Crane<'a> {
+        Crane {
+            stacks: procedure.stacks.clone(),
+            instructions: &procedure.instructions,
+            next: 0,
+            all_at_once,
+        }
+    }
 
-pub fn part_a(input: &str) -> String {
-    let (stack_str, instructions_str): (&str, &str) = input.split_once("\n\n").unwrap();
+    fn describe(&self, instruction: &Instruction) -> String {
+        format!(
+            "move {} from {} to {} {}",
+            instruction.quantity,
+            instruction.source,
+            instruction.destination,
+            if self.all_at_once {
+                "all at once"
+            } else {
+                "one at a time"
+            }
+        )
+    }
+}
 
-    let mut stacks = build_stacks(stack_str);
-    let instructions = build_instructions(instructions_str);
+impl Simulation for Crane<'_> {
+    type State = Vec<Vec<char>>;
+    type Snapshot = (Vec<Vec<char>>, usize);
 
-    for instruction in instructions {
-        for _ in 0..instruction.quantity {
-            let c = stacks[instruction.source as usize - 1].pop().unwrap();
-            stacks[instruction.destination as usize - 1].push(c);
+    fn step(&mut self) -> bool {
+        let Some(instruction) = self.instructions.get(self.next) else {
+            return false;
+        };
+        self.next += 1;
+        let description = self.describe(instruction);
+        explain!(Step, "{}", description);
+        let source = &mut self.stacks[instruction.source as usize - 1];
+        let remaining = source
+            .len()
+            .checked_sub(instruction.quantity as usize)
+            .unwrap_or_else(|| panic!("not enough crates to {}", description));
+        let mut lifted = source.split_off(remaining);
+        if !self.all_at_once {
+            // one at a time, so the top crate ends up at the bottom
+            lifted.reverse();
         }
+        self.stacks[instruction.destination as usize - 1].append(&mut lifted);
+        explain!(State, "stacks"; stacks = self.stacks);
+        true
     }
 
-    read_top_of_stacks(&stacks)
+    fn state(&self) -> &Vec<Vec<char>> {
+        &self.stacks
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.stacks.clone(), self.next)
+    }
+
+    fn restore(&mut self, (stacks, next): &Self::Snapshot) {
+        self.stacks = stacks.clone();
+        self.next = *next;
+    }
+
+    fn frame(&self) -> Option<Frame> {
+        let caption = match self.next.checked_sub(1) {
+            Some(last) => self.describe(&self.instructions[last]),
+            None => "starting stacks".to_string(),
+        };
+        Some(stacks_frame(&self.stacks, caption))
+    }
+}
+
+fn rearrange(procedure: &Procedure, all_at_once: bool) -> String {
+    let mut driver = Driver::new(Crane::new(procedure, all_at_once));
+    driver.run();
+    read_top_of_stacks(driver.state())
 }
 
-pub fn part_b(input: &str) -> String {
-    let (stack_str, instructions_str): (&str, &str) = input.split_once("\n\n").unwrap();
+pub fn part_a(procedure: &Procedure) -> String {
+    rearrange(procedure, false)
+}
 
-    let mut stacks = build_stacks(stack_str);
-    let instructions = build_instructions(instructions_str);
+pub fn part_b(procedure: &Procedure) -> String {
+    rearrange(procedure, true)
+}
 
-    for instruction in instructions {
-        let mut additions: Vec<char> = Vec::new();
-        for _ in 0..instruction.quantity {
-            let c = stacks[instruction.source as usize - 1].pop().unwrap();
-            additions.push(c);
+pub fn lint(input: &str) -> Vec<Problem> {
+    let lines: Vec<&str> = input.lines().collect();
+    let Some(blank) = lines.iter().position(|l| l.is_empty()) else {
+        return vec![Problem::new(
+            lines.len(),
+            0,
+            "missing the blank line between the stacks and the moves",
+        )];
+    };
+    if blank == 0 {
+        return vec![Problem::new(0, 0, "missing the stack drawing")];
+    }
+
+    // the last row of the drawing labels the stacks, ` 1   2   3`
+    let mut problems = Vec::new();
+    let label_idx = blank - 1;
+    let labels = fields(lines[label_idx]);
+    if labels.is_empty() {
+        problems.push(Problem::new(label_idx, 0, "missing the stack label row"));
+    }
+    for (n, (column, label)) in labels.iter().enumerate() {
+        if *label != (n + 1).to_string() {
+            problems.push(Problem::new(
+                label_idx,
+                *column,
+                format!("expected stack label `{}`", n + 1),
+            ));
         }
-        additions.reverse();
-        stacks[instruction.destination as usize - 1].append(&mut additions);
     }
+    let stack_count = labels.len();
 
-    read_top_of_stacks(&stacks)
+    for (y, row) in lines[..label_idx].iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            let valid = match x % 4 {
+                0 => c == '[' || c == ' ',
+                1 => c.is_ascii_uppercase() || c == ' ',
+                2 => c == ']' || c == ' ',
+                _ => c == ' ',
+            };
+            if !valid {
+                problems.push(Problem::new(
+                    y,
+                    x,
+                    format!("unexpected `{}` in the stack drawing", c),
+                ));
+            } else if x % 4 == 1 && c != ' ' && x / 4 >= stack_count {
+                problems.push(Problem::new(
+                    y,
+                    x,
+                    "crate is beyond the last labelled stack",
+                ));
+            }
+        }
+    }
+
+    for (idx, line) in lines.iter().enumerate().skip(blank + 1) {
+        match fields(line).as_slice() {
+            [(_, "move"), (quantity_column, quantity), (_, "from"), source, (_, "to"), destination] =>
+            {
+                problems.extend(check_number::<u32>(idx, *quantity_column, quantity));
+                for (column, stack) in [source, destination] {
+                    match stack.parse::<usize>() {
+                        Ok(n) if (1..=stack_count).contains(&n) => (),
+                        Ok(_) => problems.push(Problem::new(
+                            idx,
+                            *column,
+                            format!("there is no stack {}", stack),
+                        )),
+                        Err(_) => problems.extend(check_number::<usize>(idx, *column, stack)),
+                    }
+                }
+            }
+            _ => problems.push(Problem::new(
+                idx,
+                0,
+                "expected a move like `move 1 from 2 to 3`",
+            )),
+        }
+    }
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(5);
-        assert_eq!(part_a(&input), "BDC");
+        assert_eq!(part_a(&parse(&input)), "BDC");
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(5);
-        assert_eq!(part_b(&input), "BDA");
+        assert_eq!(part_b(&parse(&input)), "BDA");
+    }
+
+    #[test]
+    fn test_lint() {
+        let input = read_test_file(5);
+        assert_eq!(lint(&input), vec![]);
+
+        let mangled = input.replace("move 1 from 1 to 2", "move 1 from 4 to 2");
+        assert_eq!(
+            lint(&mangled),
+            vec![Problem::new(4, 12, "there is no stack 4")]
+        );
     }
 }
@@ -1,8 +1,20 @@
+use crate::lint::{check_not_empty, Problem};
+use crate::parser::{expect_parse, line, take_while1, ParseError};
 use itertools::Itertools;
+use std::collections::VecDeque;
+use std::io::BufRead;
 
-fn find_start_of_packet(input: &str, window_size: usize) -> u32 {
-    let chars: Vec<char> = input.chars().collect();
+pub struct Datastream(Vec<char>);
 
+pub fn parse(input: &str) -> Datastream {
+    let datastream = line(take_while1(
+        |c| c.is_ascii_lowercase(),
+        "a lowercase letter",
+    ));
+    Datastream(expect_parse(datastream, input).chars().collect())
+}
+
+fn find_start_of_packet(chars: &[char], window_size: usize) -> u32 {
     for i in 0..chars.len() - window_size {
         let window = &chars[i..i + window_size].to_vec();
         let unique_elems: Vec<&char> = window.iter().unique().collect();
@@ -14,28 +26,94 @@ fn find_start_of_packet(input: &str, window_size: usize) -> u32 {
     panic!("No sequence of non-reccuring chars found!")
 }
 
-pub fn part_a(input: &str) -> u32 {
-    find_start_of_packet(input, 4)
+pub fn part_a(datastream: &Datastream) -> u32 {
+    find_start_of_packet(&datastream.0, 4)
 }
 
-pub fn part_b(input: &str) -> u32 {
-    find_start_of_packet(input, 14)
+pub fn part_b(datastream: &Datastream) -> u32 {
+    find_start_of_packet(&datastream.0, 14)
+}
+
+pub fn solve_reader(part: char, reader: impl BufRead) -> u32 {
+    // the datastream is a single line, so it's read a byte at a time keeping
+    // only the current window
+    let window_size = if part == 'a' { 4 } else { 14 };
+    let mut window: VecDeque<u8> = VecDeque::with_capacity(window_size);
+    for (position, byte) in reader.bytes().enumerate() {
+        let byte = byte.unwrap_or_else(|e| panic!("could not read input: {}", e));
+        if byte == b'\n' || byte == b'\r' {
+            break;
+        }
+        if !byte.is_ascii_lowercase() {
+            let error = ParseError {
+                line: 0,
+                column: position,
+                expected: "a lowercase letter".to_string(),
+                found: format!("`{}`", byte as char),
+            };
+            panic!("invalid input at {}", error);
+        }
+        if window.len() == window_size {
+            window.pop_front();
+        }
+        window.push_back(byte);
+        if window.len() == window_size && window.iter().all_unique() {
+            return (position + 1) as u32;
+        }
+    }
+    panic!("No sequence of non-reccuring chars found!")
+}
+
+// part b looks for a window this long, so shorter inputs can't be solved
+const MIN_LENGTH: usize = 14;
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    if let Some(problem) = check_not_empty(input) {
+        return vec![problem];
+    }
+    let mut problems = Vec::new();
+    if input.trim_end().lines().count() > 1 {
+        problems.push(Problem::new(
+            1,
+            0,
+            "expected the datastream on a single line",
+        ));
+    }
+    let line = input.lines().next().unwrap();
+    if let Some(x) = line.chars().position(|c| !c.is_ascii_lowercase()) {
+        problems.push(Problem::new(0, x, "expected a lowercase letter"));
+    }
+    if line.len() < MIN_LENGTH {
+        problems.push(Problem::new(
+            0,
+            line.len(),
+            format!("datastream is shorter than {} characters", MIN_LENGTH),
+        ));
+    }
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(6);
-        assert_eq!(part_a(&input), 5);
+        assert_eq!(part_a(&parse(&input)), 5);
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(6);
-        assert_eq!(part_b(&input), 23);
+        assert_eq!(part_b(&parse(&input)), 23);
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let input = read_test_file(6);
+        assert_eq!(solve_reader('a', input.as_bytes()), 5);
+        assert_eq!(solve_reader('b', input.as_bytes()), 23);
     }
 }
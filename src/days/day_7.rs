@@ -1,84 +1,189 @@
-use advent_of_code_2022::to_u32;
+use crate::arithmetic::{self, Overflow};
+use crate::lint::{check_not_empty, check_number, fields, Problem};
+use crate::parser::{
+    expect_parse, integer, lines, literal, map, pair, preceded, rest_of_line, terminated, value,
+    Parser,
+};
+use crate::{alt, explain};
 use std::collections::HashMap;
 
-const MAX_FILE_SIZE: u32 = 100_000;
-const REQUIRED_FREE_SPACE: u32 = 30_000_000;
-const TOTAL_DISK_SPACE: u32 = 70_000_000;
+const DAY: u32 = 7;
 
-const CD_CMD: &str = "$ cd ";
-const CD_UP_CMD: &str = "$ cd ..";
-const LS_CMD: &str = "$ ls";
-const DIR_MARKER: &str = "dir ";
+const MAX_FILE_SIZE: u64 = 100_000;
+const REQUIRED_FREE_SPACE: u64 = 30_000_000;
+const TOTAL_DISK_SPACE: u64 = 70_000_000;
 
-fn dir_sizes(input: &str) -> HashMap<String, u32> {
-    let mut current_path: Vec<String> = Vec::new();
-    let mut dir_sizes: HashMap<String, u32> = HashMap::new();
+#[derive(Clone)]
+enum TerminalLine<'a> {
+    CdUp,
+    Cd(&'a str),
+    Ls,
+    Dir,
+    File(u64),
+}
+
+fn terminal_line<'a>() -> impl Parser<'a, TerminalLine<'a>> {
+    alt!(
+        value(literal("$ cd .."), TerminalLine::CdUp),
+        map(preceded(literal("$ cd "), rest_of_line()), TerminalLine::Cd),
+        value(literal("$ ls"), TerminalLine::Ls),
+        value(pair(literal("dir "), rest_of_line()), TerminalLine::Dir),
+        map(
+            terminated(integer(), pair(literal(" "), rest_of_line())),
+            TerminalLine::File
+        ),
+    )
+}
+
+// the total size of every directory, keyed by its path. Totalling can
+// overflow, which each part then reports
+pub struct DirSizes(Result<HashMap<String, u64>, Overflow>);
+
+impl DirSizes {
+    fn sizes(&self) -> Result<&HashMap<String, u64>, Overflow> {
+        self.0.as_ref().map_err(Clone::clone)
+    }
+}
+
+pub fn parse(input: &str) -> DirSizes {
+    DirSizes(dir_sizes(&expect_parse(lines(terminal_line()), input)))
+}
+
+fn dir_sizes(terminal_lines: &[TerminalLine]) -> Result<HashMap<String, u64>, Overflow> {
+    let mut current_path: Vec<&str> = Vec::new();
+    let mut dir_sizes: HashMap<String, u64> = HashMap::new();
 
     let mut already_visited_current_path = false;
 
-    input.lines().for_each(|line| {
-        let dir_str = current_path.join("/");
-
-        if line == CD_UP_CMD {
-            current_path.pop();
-        } else if line.starts_with(CD_CMD) {
-            let current_dir = line.replace(CD_CMD, "");
-            current_path.push(current_dir);
-        } else if line.starts_with(DIR_MARKER) {
-            // do nothing
-        } else if line.starts_with(LS_CMD) {
-            if dir_sizes.contains_key(&dir_str) {
-                already_visited_current_path = true;
-            } else {
-                already_visited_current_path = false;
+    for line in terminal_lines {
+        match line {
+            TerminalLine::CdUp => {
+                current_path.pop();
+            }
+            TerminalLine::Cd(dir) => {
+                current_path.push(dir);
+                explain!(Step, "cd into {}", current_path.join("/"));
             }
-        } else if already_visited_current_path == false {
-            // update current and all parent directories
-            let file_size = to_u32(line.split_once(" ").unwrap().0);
-            let mut path = current_path.clone();
-            while !path.is_empty() {
-                let dir = path.join("/");
-                *dir_sizes.entry(dir).or_insert(0) += file_size;
-                path.pop();
+            TerminalLine::Dir => (),
+            TerminalLine::Ls => {
+                let dir_str = current_path.join("/");
+                already_visited_current_path = dir_sizes.contains_key(&dir_str);
+                if already_visited_current_path {
+                    explain!(Decision, "skipping repeated ls of {}", dir_str);
+                }
             }
+            TerminalLine::File(file_size) if !already_visited_current_path => {
+                // update current and all parent directories
+                let mut path = current_path.clone();
+                while !path.is_empty() {
+                    let dir = path.join("/");
+                    let size = dir_sizes.entry(dir).or_insert(0);
+                    *size = arithmetic::add(DAY, "directory size", *size, *file_size)?;
+                    path.pop();
+                }
+            }
+            TerminalLine::File(_) => (),
         }
-    });
-    dir_sizes
+    }
+    explain!(State, "directory sizes"; dir_sizes = dir_sizes);
+    Ok(dir_sizes)
 }
 
-pub fn part_a(input: &str) -> u32 {
-    let dirs = dir_sizes(input);
-    dirs.values().filter(|x| **x < MAX_FILE_SIZE).sum()
+pub fn part_a(dirs: &DirSizes) -> Result<u64, Overflow> {
+    arithmetic::sum(
+        DAY,
+        "total size of small directories",
+        dirs.sizes()?
+            .values()
+            .copied()
+            .filter(|x| *x < MAX_FILE_SIZE),
+    )
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let dirs = dir_sizes(input);
-
-    let used_space: u32 = *dirs.get("/").unwrap();
-    let current_free_space: u32 = TOTAL_DISK_SPACE - used_space;
-    let space_to_clear: u32 = REQUIRED_FREE_SPACE - current_free_space;
+pub fn part_b(dirs: &DirSizes) -> Result<u64, Overflow> {
+    let sizes = dirs.sizes()?;
+    let used_space: u64 = *sizes.get("/").unwrap();
+    let current_free_space: u64 =
+        arithmetic::sub(DAY, "current free space", TOTAL_DISK_SPACE, used_space)?;
+    // with enough free space already there's nothing to delete
+    let space_to_clear = REQUIRED_FREE_SPACE.saturating_sub(current_free_space);
+    explain!(
+        Decision,
+        "{} used, need to clear {}",
+        used_space,
+        space_to_clear
+    );
+    if space_to_clear == 0 {
+        return Ok(0);
+    }
 
-    *dirs
+    // deleting `/` always frees enough, so it's the fallback
+    Ok(sizes
         .values()
-        .filter(|v| **v > space_to_clear)
+        .copied()
+        .filter(|v| *v >= space_to_clear)
         .min()
-        .unwrap()
+        .unwrap_or(used_space))
+}
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = check_not_empty(input).into_iter().collect();
+    for (idx, line) in input.lines().enumerate() {
+        let line_fields = fields(line);
+        match line_fields.as_slice() {
+            [(_, "$"), (_, "ls")] | [(_, "$"), (_, "cd"), _] | [(_, "dir"), _] => (),
+            [(_, "$"), (column, command), ..] => problems.push(Problem::new(
+                idx,
+                *column,
+                format!("unknown command `{}`, expected `cd <dir>` or `ls`", command),
+            )),
+            [(column, size), _] => problems.extend(check_number::<u64>(idx, *column, size)),
+            _ => problems.push(Problem::new(
+                idx,
+                0,
+                "expected a command, `dir <name>` or `<size> <name>`",
+            )),
+        }
+    }
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(7);
-        assert_eq!(part_a(&input), 95437);
+        assert_eq!(part_a(&parse(&input)), Ok(95437));
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(7);
-        assert_eq!(part_b(&input), 24933642);
+        assert_eq!(part_b(&parse(&input)), Ok(24933642));
+    }
+
+    #[test]
+    fn test_part_b_enough_free_space() {
+        let input = "$ cd /\n$ ls\n100 a.txt\n";
+        assert_eq!(part_b(&parse(input)), Ok(0));
+    }
+
+    #[test]
+    fn test_lint() {
+        let input = read_test_file(7);
+        assert_eq!(lint(&input), vec![]);
+
+        let mangled = input.replace("$ cd a", "$ cs a");
+        assert_eq!(
+            lint(&mangled),
+            vec![Problem::new(
+                6,
+                2,
+                "unknown command `cs`, expected `cd <dir>` or `ls`"
+            )]
+        );
     }
 }
@@ -1,130 +1,82 @@
-use advent_of_code_2022::to_u32;
+use crate::geometry::Direction;
+use crate::grid::{Grid, Point};
+use crate::lint::{check_grid, Problem};
+use crate::render::{gradient, Image, BLACK, GREEN};
 
-fn line_to_vec(line: &str) -> Vec<u32> {
-    line.chars().map(|c| to_u32(&c.to_string())).collect()
-}
-
-fn is_hidden_by_neighbours(
-    grid: &Vec<Vec<u32>>,
-    row_idx: usize,
-    col_idx: usize,
-    tree_height: u32,
-) -> bool {
-    let (mut hidden_left, mut hidden_right, mut hidden_up, mut hidden_down) =
-        (false, false, false, false);
-
-    // search rows to the left
-    for idx in 0..col_idx {
-        if grid[row_idx][idx] >= tree_height {
-            hidden_left = true;
-        }
-    }
-    // search rows to the right
-    for idx in col_idx + 1..grid[0].len() {
-        if grid[row_idx][idx] >= tree_height {
-            hidden_right = true;
-        }
-    }
-    // search cols to the top
-    for idx in 0..row_idx {
-        if grid[idx][col_idx] >= tree_height {
-            hidden_up = true;
-        }
-    }
+pub struct Forest(Grid<u32>);
 
-    // search cols to the bottom
-    for idx in row_idx + 1..grid.len() {
-        if grid[idx][col_idx] >= tree_height {
-            hidden_down = true;
-        }
-    }
-
-    hidden_left && hidden_right && hidden_down && hidden_up
+pub fn parse(input: &str) -> Forest {
+    Forest(Grid::parse(input, |c| c.to_digit(10).unwrap()).unwrap())
 }
 
-fn scenic_score_for_tree(
-    grid: &Vec<Vec<u32>>,
-    row_idx: usize,
-    col_idx: usize,
-    tree_height: u32,
-) -> u32 {
-    let (mut visible_left, mut visible_right, mut visible_up, mut visible_down) = (0, 0, 0, 0);
-
-    // search rows to the left
-    for idx in (0..col_idx).rev() {
-        visible_left += 1;
-        if grid[row_idx][idx] >= tree_height {
-            break;
-        }
-    }
-    // search rows to the right
-    for idx in col_idx + 1..grid[0].len() {
-        visible_right += 1;
-        if grid[row_idx][idx] >= tree_height {
-            break;
-        }
-    }
-    // search cols to the top
-    for idx in (0..row_idx).rev() {
-        visible_up += 1;
-        if grid[idx][col_idx] >= tree_height {
-            break;
-        }
-    }
-
-    // search cols to the bottom
-    for idx in row_idx + 1..grid.len() {
-        visible_down += 1;
-        if grid[idx][col_idx] >= tree_height {
-            break;
-        }
-    }
-
-    visible_left * visible_right * visible_up * visible_down
+fn is_hidden_by_neighbours(grid: &Grid<u32>, tree: Point) -> bool {
+    // hidden when every line of sight to the edge is blocked
+    let tree_height = grid[tree];
+    Direction::ALL
+        .into_iter()
+        .all(|d| grid.ray(tree, d).any(|p| grid[p] >= tree_height))
 }
 
-pub fn part_a(input: &str) -> u32 {
-    let grid: Vec<Vec<u32>> = input.lines().map(|l| line_to_vec(l)).collect();
-
-    let mut visible_trees: u32 = 0;
-
-    for (row_idx, rows) in grid.iter().enumerate() {
-        for (col_idx, tree_height) in rows.iter().enumerate() {
-            if !is_hidden_by_neighbours(&grid, row_idx, col_idx, *tree_height) {
-                visible_trees += 1;
+fn scenic_score_for_tree(grid: &Grid<u32>, tree: Point) -> u32 {
+    // in each direction, count trees up to and including the first one at
+    // least as tall as this one
+    let tree_height = grid[tree];
+    Direction::ALL
+        .into_iter()
+        .map(|d| {
+            let mut visible = 0;
+            for p in grid.ray(tree, d) {
+                visible += 1;
+                if grid[p] >= tree_height {
+                    break;
+                }
             }
-        }
-    }
-    visible_trees
+            visible
+        })
+        .product()
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let grid: Vec<Vec<u32>> = input.lines().map(|l| line_to_vec(l)).collect();
+pub fn part_a(forest: &Forest) -> u32 {
+    let grid = &forest.0;
+    grid.points()
+        .filter(|p| !is_hidden_by_neighbours(grid, *p))
+        .count() as u32
+}
 
-    let mut scenic_scores: Vec<u32> = Vec::new();
+pub fn part_b(forest: &Forest) -> u32 {
+    let grid = &forest.0;
+    grid.points()
+        .map(|p| scenic_score_for_tree(grid, p))
+        .max()
+        .unwrap()
+}
 
-    for (row_idx, rows) in grid.iter().enumerate() {
-        for (col_idx, tree_height) in rows.iter().enumerate() {
-            scenic_scores.push(scenic_score_for_tree(&grid, row_idx, col_idx, *tree_height));
-        }
-    }
-    *scenic_scores.iter().max().unwrap()
+pub fn render(input: &str) -> Image {
+    // heat map of scenic scores, brightest where the view is best
+    let grid = parse(input).0;
+    let scores = grid.map_points(|p, _| scenic_score_for_tree(&grid, p));
+    let max_score = scores.iter().map(|(_, s)| *s).max().unwrap_or(0);
+    Image::from_grid(&scores, |s| gradient(*s, max_score, BLACK, GREEN))
+}
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    check_grid(input, |c| c.is_ascii_digit(), "a tree height digit")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(8);
-        assert_eq!(part_a(&input), 21);
+        assert_eq!(part_a(&parse(&input)), 21);
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(8);
-        assert_eq!(part_b(&input), 8);
+        assert_eq!(part_b(&parse(&input)), 8);
     }
 }
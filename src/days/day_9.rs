@@ -1,184 +1,305 @@
-use advent_of_code_2022::to_u32;
-use std::ops::Add;
-use std::{collections::HashSet, ops::AddAssign, str::FromStr};
-use Direction::{Down, Left, Right, Up};
+use crate::animation::Frame;
+use crate::geometry::{Direction, Point};
+use crate::lint::{check_not_empty, check_number, Problem};
+use crate::parser::{expect_parse, integer, lines, literal, map, one_of, separated_pair, Parser};
+use crate::render::{Image, BLACK, GOLD};
+use crate::simulation::{Driver, Simulation};
+use crate::stream::{for_each_line, parse_line};
+use crate::{animate, explain};
+use std::collections::HashSet;
+use std::io::BufRead;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Coords {
-    x: i32,
-    y: i32,
+struct Movement {
+    direction: Direction,
+    steps: u32,
 }
 
-impl Add for Coords {
-    type Output = Coords;
+fn movement<'a>() -> impl Parser<'a, Movement> {
+    // e.g. `R 4`
+    map(
+        separated_pair(one_of("UDLR"), literal(" "), integer()),
+        |(direction, steps)| Movement {
+            direction: match direction {
+                'U' => Direction::Up,
+                'D' => Direction::Down,
+                'L' => Direction::Left,
+                _ => Direction::Right,
+            },
+            steps,
+        },
+    )
+}
 
-    fn add(self, rhs: Coords) -> Coords {
-        Coords {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+fn tail_movement(head_pos: Point, tail_pos: Point) -> Point {
+    if head_pos.is_adjacent(tail_pos) {
+        Point::ORIGIN // no movement required
+    } else if head_pos.chebyshev(tail_pos) <= 2 {
+        // one step towards the head on each axis it's away on
+        (head_pos - tail_pos).signum()
+    } else {
+        panic!(
+            "Head [{:?}] and Tail [{:?}] are separated by an invalid distance",
+            head_pos, tail_pos
+        );
     }
 }
 
-impl AddAssign for Coords {
-    fn add_assign(&mut self, rhs: Coords) {
-        *self = *self + rhs;
+fn rope_frame(knot_positions: &[Point], visited_coords: &HashSet<Point>) -> Frame {
+    // the head is `H` and the knots behind it are numbered, drawn tail first
+    // so the head stays on top
+    let mut frame = Frame::new(format!(
+        "head {:?}, tail visited {}",
+        knot_positions[0],
+        visited_coords.len()
+    ));
+    for c in visited_coords {
+        frame.set(c.x, c.y, '#');
+    }
+    for (idx, knot) in knot_positions.iter().enumerate().rev() {
+        let label = if idx == 0 {
+            'H'
+        } else {
+            char::from_digit(idx as u32 % 10, 10).unwrap()
+        };
+        frame.set(knot.x, knot.y, label);
     }
+    frame.focus(knot_positions[0].x, knot_positions[0].y);
+    frame
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+pub struct Motions(Vec<Movement>);
+
+pub fn parse(input: &str) -> Motions {
+    Motions(expect_parse(lines(movement()), input))
 }
 
-impl FromStr for Direction {
-    type Err = ();
+#[derive(Clone)]
+struct Rope {
+    knot_positions: Vec<Point>,
+    visited_coords: HashSet<Point>,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "U" => Ok(Up),
-            "D" => Ok(Down),
-            "L" => Ok(Left),
-            "R" => Ok(Right),
-            _ => Err(()),
+impl Rope {
+    fn new(rope_length: usize) -> Rope {
+        Rope {
+            knot_positions: vec![Point::ORIGIN; rope_length],
+            visited_coords: HashSet::from([Point::ORIGIN]),
         }
     }
-}
 
-struct Movement {
-    direction: Direction,
-    steps: u32,
-}
-
-impl FromStr for Movement {
-    type Err = ();
+    fn explain_movement(movement: &Movement) {
+        explain!(
+            Step,
+            "move head {:?} {} steps",
+            movement.direction,
+            movement.steps
+        );
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let direction_str = &s[..1];
-        let steps_str = &s[2..];
+    fn step_head(&mut self, direction: &Direction) {
+        let knot_positions = &mut self.knot_positions;
+        knot_positions[0] += direction.offset();
+        for idx in 1..knot_positions.len() {
+            let movement = tail_movement(knot_positions[idx - 1], knot_positions[idx]);
+            knot_positions[idx] += movement;
+        }
+        self.visited_coords.insert(*knot_positions.last().unwrap());
+        explain!(State, "knots moved"; knots = knot_positions);
+    }
 
-        let direction = Direction::from_str(direction_str);
-        let steps = to_u32(steps_str);
+    fn apply(&mut self, movement: &Movement) {
+        Rope::explain_movement(movement);
+        for _ in 0..movement.steps {
+            self.step_head(&movement.direction);
+            animate!(rope_frame(&self.knot_positions, &self.visited_coords));
+        }
+    }
 
-        direction.map(|d| Movement {
-            direction: d,
-            steps: steps,
-        })
+    fn into_visited_coords(self) -> HashSet<Point> {
+        explain!(
+            Decision,
+            "tail visited {} unique coords",
+            self.visited_coords.len()
+        );
+        self.visited_coords
     }
 }
 
-fn head_movement(direction: Direction) -> Coords {
-    match direction {
-        Up => Coords { x: 0, y: 1 },
-        Down => Coords { x: 0, y: -1 },
-        Left => Coords { x: -1, y: 0 },
-        Right => Coords { x: 1, y: 0 },
-    }
+// moves the head one square per step, working through the motions in order
+struct RopeSimulation<'a> {
+    rope: Rope,
+    movements: &'a [Movement],
+    next: usize,
+    // steps left of the motion being worked on
+    remaining: u32,
 }
 
-fn tail_movement(head_pos: Coords, tail_pos: Coords) -> Coords {
-    let x_dist = head_pos.x - tail_pos.x;
-    let y_dist = head_pos.y - tail_pos.y;
+impl<'a> RopeSimulation<'a> {
+    fn new(movements: &'a [Movement], rope_length: usize) -> RopeSimulation<'a> {
+        RopeSimulation {
+            rope: Rope::new(rope_length),
+            movements,
+            next: 0,
+            remaining: 0,
+        }
+    }
+}
 
-    let head_is_within_one = x_dist.abs() <= 1 && y_dist.abs() <= 1;
-    let head_is_within_two =
-        (x_dist.abs() <= 2 && y_dist.abs() <= 2) || (y_dist.abs() <= 2 && x_dist.abs() <= 2);
+impl Simulation for RopeSimulation<'_> {
+    type State = Rope;
+    type Snapshot = (Rope, usize, u32);
+
+    fn step(&mut self) -> bool {
+        while self.remaining == 0 {
+            let Some(movement) = self.movements.get(self.next) else {
+                return false;
+            };
+            Rope::explain_movement(movement);
+            self.next += 1;
+            self.remaining = movement.steps;
+        }
+        self.remaining -= 1;
+        self.rope
+            .step_head(&self.movements[self.next - 1].direction);
+        true
+    }
 
-    if head_is_within_one {
-        return Coords { x: 0, y: 0 }; // no movement required
-    } else if head_is_within_two {
-        let capped_x = (-1).max(x_dist.min(1));
-        let capped_y = (-1).max(y_dist.min(1));
-        return Coords {
-            x: capped_x,
-            y: capped_y,
-        };
-    } else {
-        panic!(
-            "Head [{:?}] and Tail [{:?}] are separated by an invalid distance",
-            head_pos, tail_pos
-        );
+    fn state(&self) -> &Rope {
+        &self.rope
     }
-}
 
-pub fn unique_coords_visited_by_tail(input: &str, rope_length: usize) -> u32 {
-    let movements: Vec<Movement> = input
-        .lines()
-        .map(|m| Movement::from_str(m).unwrap())
-        .collect();
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.rope.clone(), self.next, self.remaining)
+    }
 
-    let mut knot_positions: Vec<Coords> = Vec::new();
-    for _ in 0..rope_length {
-        knot_positions.push(Coords { x: 0, y: 0 });
+    fn restore(&mut self, (rope, next, remaining): &Self::Snapshot) {
+        self.rope = rope.clone();
+        self.next = *next;
+        self.remaining = *remaining;
     }
 
-    let mut visited_coords: HashSet<Coords> = HashSet::from([*knot_positions.last().unwrap()]);
-    for movement in movements {
-        for _ in 0..movement.steps {
-            knot_positions[0] += head_movement(movement.direction);
-            for idx in 1..knot_positions.len() {
-                let movement = tail_movement(knot_positions[idx - 1], knot_positions[idx]);
-                knot_positions[idx] += movement;
-            }
-            visited_coords.insert(knot_positions[rope_length - 1]);
-        }
+    fn frame(&self) -> Option<Frame> {
+        Some(rope_frame(
+            &self.rope.knot_positions,
+            &self.rope.visited_coords,
+        ))
     }
-    visited_coords.len().try_into().unwrap()
 }
 
-pub fn part_a(input: &str) -> u32 {
-    unique_coords_visited_by_tail(input, 2)
+fn coords_visited_by_tail(movements: &[Movement], rope_length: usize) -> HashSet<Point> {
+    let mut driver = Driver::new(RopeSimulation::new(movements, rope_length));
+    driver.run();
+    driver.into_simulation().rope.into_visited_coords()
+}
+
+fn unique_coords_visited_by_tail(movements: &[Movement], rope_length: usize) -> u32 {
+    coords_visited_by_tail(movements, rope_length)
+        .len()
+        .try_into()
+        .unwrap()
+}
+
+pub fn part_a(motions: &Motions) -> u32 {
+    unique_coords_visited_by_tail(&motions.0, 2)
 }
 
-pub fn part_b(input: &str) -> u32 {
-    unique_coords_visited_by_tail(input, 10)
+pub fn part_b(motions: &Motions) -> u32 {
+    unique_coords_visited_by_tail(&motions.0, 10)
+}
+
+pub fn solve_reader(part: char, reader: impl BufRead) -> u32 {
+    // the motions aren't kept, memory grows with the coords the tail visits
+    // rather than the length of the input
+    let mut rope = Rope::new(if part == 'a' { 2 } else { 10 });
+    for_each_line(reader, |idx, line| {
+        rope.apply(&parse_line(movement(), idx, line))
+    });
+    rope.into_visited_coords().len().try_into().unwrap()
+}
+
+pub fn render(input: &str) -> Image {
+    // images put positive y at the top, so y is flipped to draw up as up
+    let visited = coords_visited_by_tail(&parse(input).0, 10);
+    Image::from_points(visited.iter().map(|c| (c.x, -c.y)), GOLD, BLACK)
+}
+
+pub fn lint(input: &str) -> Vec<Problem> {
+    // e.g. `R 4`
+    let mut problems: Vec<Problem> = check_not_empty(input).into_iter().collect();
+    for (idx, line) in input.lines().enumerate() {
+        match line.split_once(' ') {
+            Some((direction, steps)) => {
+                if !matches!(direction, "U" | "D" | "L" | "R") {
+                    problems.push(Problem::new(idx, 0, "expected `U`, `D`, `L` or `R`"));
+                }
+                problems.extend(check_number::<u32>(idx, direction.len() + 1, steps));
+            }
+            None => problems.push(Problem::new(idx, 0, "expected a move like `R 4`")),
+        }
+    }
+    problems
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2022::read_test_file;
+    use crate::read_test_file;
 
     #[test]
     fn test_tail_movement() {
-        let same_pos = tail_movement(Coords { x: 0, y: 0 }, Coords { x: 0, y: 0 });
-        assert_eq!(same_pos, Coords { x: 0, y: 0 });
+        let same_pos = tail_movement(Point::new(0, 0), Point::new(0, 0));
+        assert_eq!(same_pos, Point::new(0, 0));
 
-        let tail_below = tail_movement(Coords { x: 0, y: 0 }, Coords { x: 0, y: -1 });
-        assert_eq!(tail_below, Coords { x: 0, y: 0 });
+        let tail_below = tail_movement(Point::new(0, 0), Point::new(0, -1));
+        assert_eq!(tail_below, Point::new(0, 0));
 
-        let head_right = tail_movement(Coords { x: 1, y: 0 }, Coords { x: 0, y: 0 });
-        assert_eq!(head_right, Coords { x: 0, y: 0 });
+        let head_right = tail_movement(Point::new(1, 0), Point::new(0, 0));
+        assert_eq!(head_right, Point::new(0, 0));
 
-        let tail_diag = tail_movement(Coords { x: 1, y: 1 }, Coords { x: 0, y: 0 });
-        assert_eq!(tail_diag, Coords { x: 0, y: 0 });
+        let tail_diag = tail_movement(Point::new(1, 1), Point::new(0, 0));
+        assert_eq!(tail_diag, Point::new(0, 0));
 
-        let head_two_left = tail_movement(Coords { x: -2, y: 0 }, Coords { x: 0, y: 0 });
-        assert_eq!(head_two_left, Coords { x: -1, y: 0 });
+        let head_two_left = tail_movement(Point::new(-2, 0), Point::new(0, 0));
+        assert_eq!(head_two_left, Point::new(-1, 0));
 
-        let head_two_right = tail_movement(Coords { x: 2, y: 0 }, Coords { x: 0, y: 0 });
-        assert_eq!(head_two_right, Coords { x: 1, y: 0 });
+        let head_two_right = tail_movement(Point::new(2, 0), Point::new(0, 0));
+        assert_eq!(head_two_right, Point::new(1, 0));
 
-        let head_one_down_and_two_left =
-            tail_movement(Coords { x: -1, y: 0 }, Coords { x: 1, y: 1 });
-        assert_eq!(head_one_down_and_two_left, Coords { x: -1, y: -1 });
+        let head_one_down_and_two_left = tail_movement(Point::new(-1, 0), Point::new(1, 1));
+        assert_eq!(head_one_down_and_two_left, Point::new(-1, -1));
 
-        let head_two_up_and_one_left = tail_movement(Coords { x: -1, y: 2 }, Coords { x: 0, y: 0 });
-        assert_eq!(head_two_up_and_one_left, Coords { x: -1, y: 1 });
+        let head_two_up_and_one_left = tail_movement(Point::new(-1, 2), Point::new(0, 0));
+        assert_eq!(head_two_up_and_one_left, Point::new(-1, 1));
     }
 
     #[test]
     fn test_part_a() {
         let input = read_test_file(9);
-        assert_eq!(part_a(&input), 88);
+        assert_eq!(part_a(&parse(&input)), 88);
     }
 
     #[test]
     fn test_part_b() {
         let input = read_test_file(9);
-        assert_eq!(part_b(&input), 36);
+        assert_eq!(part_b(&parse(&input)), 36);
+    }
+
+    #[test]
+    fn test_rewind() {
+        let motions = parse(&read_test_file(9));
+        let mut driver = Driver::new(RopeSimulation::new(&motions.0, 10)).with_snapshots(5);
+        driver.run();
+        let end = driver.state().knot_positions.clone();
+        driver.rewind_to(7);
+        assert_eq!(driver.steps(), 7);
+        driver.run();
+        assert_eq!(driver.state().knot_positions, end);
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let input = read_test_file(9);
+        assert_eq!(solve_reader('a', input.as_bytes()), 88);
+        assert_eq!(solve_reader('b', input.as_bytes()), 36);
     }
 }
@@ -1,3 +1,14 @@
+use crate::arithmetic::Overflow;
+use crate::lint::Problem;
+use crate::ocr::OcrError;
+use crate::plugins::{self, Plugin};
+use crate::render::Image;
+use crate::ReturnType;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::fmt;
+use std::io::BufRead;
+
 pub mod day_1;
 pub mod day_10;
 pub mod day_11;
@@ -10,3 +21,288 @@ pub mod day_6;
 pub mod day_7;
 pub mod day_8;
 pub mod day_9;
+
+pub type Renderer = fn(&str) -> Image;
+pub type Linter = fn(&str) -> Vec<Problem>;
+
+// each day parses its input into its own type, which both parts then share.
+// The registry doesn't know those types, so they're passed around as `Any`
+pub type Parsed = Box<dyn Any>;
+pub type Parser = fn(&str) -> Parsed;
+
+// why a part has no answer, as opposed to the panics of malformed input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SolveError {
+    // caught by `--checked`
+    Overflow(Overflow),
+    // the part drew letters that couldn't be read
+    Ocr(OcrError),
+}
+
+impl SolveError {
+    // a short name for JSON output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SolveError::Overflow(_) => "overflow",
+            SolveError::Ocr(_) => "ocr",
+        }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Overflow(overflow) => write!(f, "{}", overflow),
+            SolveError::Ocr(error) => write!(f, "could not read the letters: {}", error),
+        }
+    }
+}
+
+impl From<Overflow> for SolveError {
+    fn from(overflow: Overflow) -> SolveError {
+        SolveError::Overflow(overflow)
+    }
+}
+
+impl From<OcrError> for SolveError {
+    fn from(error: OcrError) -> SolveError {
+        SolveError::Ocr(error)
+    }
+}
+
+pub type Answer = Result<ReturnType, SolveError>;
+
+// parts return their answer directly, or in a `Result` if they can fail
+pub trait IntoAnswer {
+    fn into_answer(self) -> Answer;
+}
+
+impl<T: Into<ReturnType>> IntoAnswer for T {
+    fn into_answer(self) -> Answer {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<ReturnType>, E: Into<SolveError>> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Answer {
+        self.map(Into::into).map_err(Into::into)
+    }
+}
+
+// days whose logic is naturally streaming can also solve straight from a
+// reader, without holding the input in memory
+pub type StreamSolver = fn(char, &mut dyn BufRead) -> Answer;
+
+// choices that change what a part returns rather than how it solves
+#[derive(Clone, Copy)]
+pub struct Options {
+    // read the letters parts draw, instead of returning the picture
+    pub ocr: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options { ocr: true }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Solver {
+    Builtin(fn(&dyn Any, Options) -> Answer),
+    Plugin(&'static Plugin, char),
+}
+
+impl Solver {
+    pub fn solve(&self, parsed: &dyn Any, options: Options) -> Answer {
+        match self {
+            Solver::Builtin(solver) => solver(parsed, options),
+            Solver::Plugin(plugin, part) => {
+                // plugins parse for themselves, so they're given the raw input
+                let input = parsed.downcast_ref::<String>().unwrap();
+                Ok(ReturnType::String(plugin.solve(*part, input)))
+            }
+        }
+    }
+}
+
+// solvers panic on malformed input, this is what they said
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "solver panicked".to_string(),
+    }
+}
+
+fn run<T: 'static, R: IntoAnswer>(parsed: &dyn Any, part: impl Fn(&T) -> R) -> Answer {
+    // a mismatch here means a solution was registered with another day's parser
+    let parsed = parsed
+        .downcast_ref::<T>()
+        .expect("parsed input is not the type the solver expects");
+    part(parsed).into_answer()
+}
+
+// the solutions in this module are all for 2022. Solutions for other years
+// should live in their own module with their own entries in `registry`
+pub const YEAR: u32 = 2022;
+
+pub struct Solution {
+    pub year: u32,
+    pub day: u32,
+    pub parse: Parser,
+    pub part_a: Solver,
+    pub part_b: Solver,
+    pub render: Option<Renderer>,
+    pub lint: Option<Linter>,
+    pub stream: Option<StreamSolver>,
+}
+
+impl Solution {
+    pub fn is_plugin(&self) -> bool {
+        matches!(self.part_a, Solver::Plugin(..))
+    }
+
+    pub fn part(&self, part: char) -> Option<Solver> {
+        let solver = match part {
+            'a' => self.part_a,
+            'b' => self.part_b,
+            _ => return None,
+        };
+        match solver {
+            // plugins may only solve one of the parts
+            Solver::Plugin(plugin, p) if !plugin.has_part(p) => None,
+            solver => Some(solver),
+        }
+    }
+
+    pub fn solve(&self, part: char, input: &str, options: Options) -> Option<Answer> {
+        // parses and solves a single part, callers running both parts should
+        // parse once themselves
+        let solver = self.part(part)?;
+        Some(solver.solve((self.parse)(input).as_ref(), options))
+    }
+}
+
+macro_rules! solution {
+    ($day:expr, $module:ident) => {
+        solution!(@parts $day, $module, |parsed, _| run(parsed, $module::part_b))
+    };
+    // part b draws letters, and is told whether to read them. Must come last
+    ($day:expr, $module:ident, ocr) => {
+        solution!(@parts $day, $module, |parsed, options| {
+            run(parsed, |p| $module::part_b(p, options.ocr))
+        })
+    };
+    (@parts $day:expr, $module:ident, $part_b:expr) => {
+        Solution {
+            year: YEAR,
+            day: $day,
+            parse: |input| Box::new($module::parse(input)),
+            part_a: Solver::Builtin(|parsed, _| run(parsed, $module::part_a)),
+            part_b: Solver::Builtin($part_b),
+            render: None,
+            lint: Some($module::lint),
+            stream: None,
+        }
+    };
+    ($day:expr, $module:ident, render $(, $rest:ident)*) => {
+        Solution {
+            render: Some($module::render),
+            ..solution!($day, $module $(, $rest)*)
+        }
+    };
+    ($day:expr, $module:ident, stream $(, $rest:ident)*) => {
+        Solution {
+            stream: Some(|part, reader| $module::solve_reader(part, reader).into_answer()),
+            ..solution!($day, $module $(, $rest)*)
+        }
+    };
+}
+
+fn plugin_solution(plugin: &'static Plugin) -> Solution {
+    Solution {
+        year: plugin.year,
+        day: plugin.day,
+        parse: |input| Box::new(input.to_string()),
+        part_a: Solver::Plugin(plugin, 'a'),
+        part_b: Solver::Plugin(plugin, 'b'),
+        render: None,
+        lint: None,
+        stream: None,
+    }
+}
+
+fn is_builtin(year: u32, day: u32) -> bool {
+    builtin().iter().any(|s| s.year == year && s.day == day)
+}
+
+pub fn load_plugins() {
+    for error in plugins::load(&plugins::plugins_dir()) {
+        eprintln!("Skipped plugin: {}", error);
+    }
+    for plugin in plugins::loaded() {
+        if is_builtin(plugin.year, plugin.day) {
+            eprintln!(
+                "Skipped plugin: {} is shadowed by the built-in solution for {} day {}",
+                plugin.path.display(),
+                plugin.year,
+                plugin.day
+            );
+        } else {
+            eprintln!(
+                "Loaded plugin {} for {} day {}",
+                plugin.path.display(),
+                plugin.year,
+                plugin.day
+            );
+        }
+    }
+}
+
+// built-in solutions come first, so a plugin never replaces one of these
+pub fn registry() -> Vec<Solution> {
+    let mut solutions = builtin();
+    let mut seen: Vec<(u32, u32)> = solutions.iter().map(|s| (s.year, s.day)).collect();
+    for plugin in plugins::loaded() {
+        if !seen.contains(&(plugin.year, plugin.day)) {
+            seen.push((plugin.year, plugin.day));
+            solutions.push(plugin_solution(plugin));
+        }
+    }
+    solutions
+}
+
+// every implemented day must be registered here to be runnable
+fn builtin() -> Vec<Solution> {
+    vec![
+        solution!(1, day_1, stream),
+        solution!(2, day_2, stream),
+        solution!(3, day_3, stream),
+        solution!(4, day_4, stream),
+        solution!(5, day_5),
+        solution!(6, day_6, stream),
+        solution!(7, day_7),
+        solution!(8, day_8, render),
+        solution!(9, day_9, render, stream),
+        solution!(10, day_10, render, ocr),
+        solution!(11, day_11),
+        solution!(12, day_12, render),
+    ]
+}
+
+pub fn find(year: u32, day: u32) -> Option<Solution> {
+    registry()
+        .into_iter()
+        .find(|s| s.year == year && s.day == day)
+}
+
+pub fn years() -> Vec<u32> {
+    let mut years: Vec<u32> = registry().iter().map(|s| s.year).collect();
+    years.sort();
+    years.dedup();
+    years
+}
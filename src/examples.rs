@@ -0,0 +1,132 @@
+// pulls the worked example out of a saved puzzle page. Each part of the puzzle
+// is an `<article class="day-desc">`; the example input is a `<pre><code>`
+// block and the part's answer for it is the last emphasised `<code><em>`
+// in that article. Part two's article only appears once part one is solved
+const ARTICLE_START: &str = "<article";
+const ARTICLE_END: &str = "</article>";
+const BLOCK_START: &str = "<pre><code>";
+const BLOCK_END: &str = "</code></pre>";
+const ANSWER_STARTS: [&str; 2] = ["<code><em>", "<em><code>"];
+const ANSWER_ENDS: [&str; 2] = ["</em></code>", "</code></em>"];
+
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    // every example block on the page, in order. The first is nearly always
+    // the one the answers are for
+    pub blocks: Vec<String>,
+    // the expected answer for each part found, part a first
+    pub answers: Vec<String>,
+}
+
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(from) = rest.find(start) {
+        let after = &rest[from + start.len()..];
+        let Some(to) = after.find(end) else {
+            break;
+        };
+        found.push(&after[..to]);
+        rest = &after[to + end.len()..];
+    }
+    found
+}
+
+fn strip_tags(html: &str) -> String {
+    // examples highlight parts of the input with `<em>`, only the text is kept
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text
+}
+
+fn unescape(text: &str) -> String {
+    // `&amp;` last so `&amp;lt;` becomes `&lt;`, not `<`
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn answer(article: &str) -> Option<String> {
+    ANSWER_STARTS
+        .iter()
+        .zip(ANSWER_ENDS)
+        .filter_map(|(start, end)| {
+            // the latest of either style wins when a page mixes them
+            let from = article.rfind(start)?;
+            let last = *between(&article[from..], start, end).first()?;
+            Some((from, last))
+        })
+        .max()
+        .map(|(_, answer)| unescape(&strip_tags(answer)))
+}
+
+pub fn extract(html: &str) -> Example {
+    let articles: Vec<&str> = between(html, ARTICLE_START, ARTICLE_END);
+    let blocks = articles
+        .iter()
+        .flat_map(|article| between(article, BLOCK_START, BLOCK_END))
+        .map(|block| unescape(&strip_tags(block)))
+        .collect();
+    let answers = articles.iter().filter_map(|a| answer(a)).collect();
+    Example { blocks, answers }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Calorie Counting ---</h2>
+<p>For example:</p>
+<pre><code>1000
+<em>2000</em>
+
+3000
+</code></pre>
+<p>The elf with <code>3000</code> carries <code><em>3000</em></code> calories.</p>
+</article>
+<p>Your puzzle answer was <code>123</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>In the example above, the total is <em><code>6000</code></em>.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_extract() {
+        let example = extract(PAGE);
+        assert_eq!(example.blocks, vec!["1000\n2000\n\n3000\n".to_string()]);
+        assert_eq!(
+            example.answers,
+            vec!["3000".to_string(), "6000".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(
+            unescape(&strip_tags("<em>$ cd /</em>\n&lt;a&gt; &amp;lt;")),
+            "$ cd /\n<a> &lt;"
+        );
+    }
+
+    #[test]
+    fn test_extract_without_examples() {
+        assert_eq!(
+            extract("<p>not a puzzle</p>"),
+            Example {
+                blocks: vec![],
+                answers: vec![]
+            }
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{days, ReturnType, DEFAULT_YEAR};
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+// the C interface to the solvers, declared in include/advent_of_code_2022.h.
+// Keep the two in sync when anything here changes
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AocKind {
+    Error = 0,
+    Unsigned = 1,
+    Signed = 2,
+    Text = 3,
+    Grid = 4,
+}
+
+#[repr(C)]
+pub struct AocResult {
+    pub kind: AocKind,
+    pub unsigned_value: u64,
+    pub signed_value: i64,
+    // the answer as text for every kind (rows joined by `\n` for grids), or
+    // the error message. Owned by the result and freed with it
+    pub text: *mut c_char,
+}
+
+fn c_string(s: &str) -> *mut c_char {
+    // interior NULs can't cross into C, so they are dropped
+    CString::new(s.replace('\0', "")).unwrap().into_raw()
+}
+
+fn error(message: &str) -> AocResult {
+    AocResult {
+        kind: AocKind::Error,
+        unsigned_value: 0,
+        signed_value: 0,
+        text: c_string(message),
+    }
+}
+
+fn to_result(answer: &ReturnType) -> AocResult {
+    // 128-bit answers that don't fit in 64 bits are only available as text
+    let (kind, unsigned_value, signed_value) = match *answer {
+        ReturnType::UInt32(v) => (AocKind::Unsigned, v as u64, 0),
+        ReturnType::UInt64(v) => (AocKind::Unsigned, v, 0),
+        ReturnType::USize(v) => (AocKind::Unsigned, v as u64, 0),
+        ReturnType::UInt128(v) => match u64::try_from(v) {
+            Ok(v) => (AocKind::Unsigned, v, 0),
+            Err(_) => (AocKind::Text, 0, 0),
+        },
+        ReturnType::Int64(v) => (AocKind::Signed, 0, v),
+        ReturnType::Int128(v) => match i64::try_from(v) {
+            Ok(v) => (AocKind::Signed, 0, v),
+            Err(_) => (AocKind::Text, 0, 0),
+        },
+        ReturnType::String(_) => (AocKind::Text, 0, 0),
+        ReturnType::Grid(_) => (AocKind::Grid, 0, 0),
+    };
+    AocResult {
+        kind,
+        unsigned_value,
+        signed_value,
+        text: c_string(&answer.to_string()),
+    }
+}
+
+fn solve(day: u32, part: c_char, input: *const c_char, len: usize) -> AocResult {
+    let part = part as u8 as char;
+    let Some(solution) = days::find(DEFAULT_YEAR, day).filter(|s| s.part(part).is_some()) else {
+        return error(&format!("no solution for day {} part {}", day, part));
+    };
+    if input.is_null() {
+        return error("input is null");
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(input as *const u8, len) };
+    let Ok(input) = std::str::from_utf8(bytes) else {
+        return error("input is not valid UTF-8");
+    };
+    // solvers panic on malformed input, and unwinding into C is undefined
+    // behaviour, so panics become error results
+    match panic::catch_unwind(AssertUnwindSafe(|| {
+        solution
+            .solve(part, input, days::Options::default())
+            .unwrap()
+    })) {
+        Ok(Ok(answer)) => to_result(&answer),
+        Ok(Err(solve_error)) => error(&solve_error.to_string()),
+        Err(payload) => error(&days::panic_message(payload.as_ref())),
+    }
+}
+
+/// Solve `part` (`'a'` or `'b'`) of `day` for the `len` bytes of `input`.
+/// The result is never null and must be released with `aoc_result_free`.
+///
+/// # Safety
+/// `input` must point to at least `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u32,
+    part: c_char,
+    input: *const c_char,
+    len: usize,
+) -> *mut AocResult {
+    Box::into_raw(Box::new(solve(day, part, input, len)))
+}
+
+/// Release a result returned by `aoc_solve`. Null is ignored.
+///
+/// # Safety
+/// `result` must come from `aoc_solve` and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_result_free(result: *mut AocResult) {
+    if result.is_null() {
+        return;
+    }
+    let result = Box::from_raw(result);
+    if !result.text.is_null() {
+        drop(CString::from_raw(result.text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_test_file;
+    use std::ffi::CStr;
+    use std::ptr;
+
+    fn solve_test_input(day: u32, part: char) -> AocResult {
+        let input = read_test_file(day);
+        solve(
+            day,
+            part as c_char,
+            input.as_ptr() as *const c_char,
+            input.len(),
+        )
+    }
+
+    fn text(result: &AocResult) -> &str {
+        unsafe { CStr::from_ptr(result.text) }.to_str().unwrap()
+    }
+
+    #[test]
+    fn test_solve() {
+        let result = solve_test_input(1, 'a');
+        assert_eq!(result.kind, AocKind::Unsigned);
+        assert_eq!(result.unsigned_value, 101);
+        assert_eq!(text(&result), "101");
+        unsafe { aoc_result_free(Box::into_raw(Box::new(result))) };
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let result = solve_test_input(1, 'c');
+        assert_eq!(result.kind, AocKind::Error);
+        assert_eq!(text(&result), "no solution for day 1 part c");
+
+        let result = solve(1, 'a' as c_char, ptr::null(), 0);
+        assert_eq!(text(&result), "input is null");
+    }
+}
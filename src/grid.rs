@@ -0,0 +1,343 @@
+use crate::geometry::{self, Direction};
+use crate::ReturnType;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+// a rectangular grid stored row by row. Points are `(x, y)` with x counting
+// columns from the left and y counting rows from the top, as puzzle maps are
+// drawn. Moving around uses `geometry` offsets, which share that orientation
+pub type Point = (usize, usize);
+
+#[derive(Debug, PartialEq)]
+pub enum GridError {
+    Ragged {
+        row: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::Ragged {
+                row,
+                width,
+                expected,
+            } => write!(
+                f,
+                "row {} is {} wide, expected {} like the first row",
+                row + 1,
+                width,
+                expected
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, GridError> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (row, cells_in_row) in rows.into_iter().enumerate() {
+            if cells_in_row.len() != width {
+                return Err(GridError::Ragged {
+                    row,
+                    width: cells_in_row.len(),
+                    expected: width,
+                });
+            }
+            cells.extend(cells_in_row);
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> T) -> Result<Grid<T>, GridError> {
+        // one row per line, one cell per character
+        Grid::from_rows(
+            input
+                .lines()
+                .map(|line| line.chars().map(&mut cell).collect())
+                .collect(),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.contains(point)
+            .then(|| &self.cells[point.1 * self.width + point.0])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            Some(&mut self.cells[point.1 * self.width + point.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn step(&self, (x, y): Point, offset: geometry::Point) -> Option<Point> {
+        // the point `offset` away, if it's on the grid
+        let x = x.checked_add_signed(offset.x as isize)?;
+        let y = y.checked_add_signed(offset.y as isize)?;
+        self.contains((x, y)).then_some((x, y))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| self.step(point, d.offset()))
+    }
+
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        geometry::Point::ORIGIN
+            .neighbours8()
+            .filter_map(move |offset| self.step(point, offset))
+    }
+
+    pub fn ray(&self, from: Point, direction: Direction) -> impl Iterator<Item = Point> + '_ {
+        // every point from `from` to the edge of the grid, not including `from`
+        let offset = direction.offset();
+        std::iter::successors(self.step(from, offset), move |p| self.step(*p, offset))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a zero width, and a zero width grid has no cells
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        // past the width, skipping would wrap onto the start of a later row
+        assert!(
+            x < self.width,
+            "column {} is outside the {}x{} grid",
+            x,
+            self.width,
+            self.height
+        );
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn map_points<U>(&self, mut f: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(p, cell)| f(p, cell)).collect(),
+        }
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        self.rows().map(|row| row.to_vec()).collect()
+    }
+
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.rebuild(self.height, self.width, |x, y| (y, x))
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        // the bottom row becomes the leftmost column
+        let height = self.height;
+        self.rebuild(height, self.width, move |x, y| (y, height - 1 - x))
+    }
+
+    pub fn rotate_anticlockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.rebuild(self.height, width, move |x, y| (width - 1 - y, x))
+    }
+
+    fn rebuild(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> Point,
+    ) -> Grid<T>
+    where
+        T: Clone,
+    {
+        // builds a `width` by `height` grid, taking each new (x, y) from
+        // `source(x, y)` in this grid
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self[source(x, y)].clone())
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside the {}x{} grid",
+                point, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", point, width, height))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse(s, |c| c)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Grid<char>> for ReturnType {
+    fn from(grid: Grid<char>) -> Self {
+        ReturnType::Grid(grid.to_rows())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef".parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+        assert_eq!(
+            "ab\nc".parse::<Grid<char>>(),
+            Err(GridError::Ragged {
+                row: 1,
+                width: 1,
+                expected: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_neighbours_and_rays() {
+        let grid = grid();
+        let neighbours: Vec<Point> = grid.neighbours4((0, 0)).collect();
+        assert_eq!(neighbours, vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8((1, 0)).count(), 5);
+
+        let ray: Vec<char> = grid
+            .ray((0, 1), Direction::Right)
+            .map(|p| grid[p])
+            .collect();
+        assert_eq!(ray, vec!['e', 'f']);
+        assert_eq!(grid.ray((0, 1), Direction::Left).count(), 0);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is outside the 3x2 grid")]
+    fn test_column_outside_grid() {
+        grid().column(3).count();
+    }
+
+    #[test]
+    fn test_transpose_and_rotate() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
+    }
+}
@@ -1,21 +1,155 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 use std::{env, fs};
 
+pub mod alloc_counter;
+pub mod animation;
+pub mod answers;
+pub mod arithmetic;
+pub mod checks;
+pub mod crypto;
+pub mod days;
+pub mod examples;
+pub mod ffi;
+pub mod geometry;
+pub mod grid;
+pub mod lint;
+pub mod ocr;
+pub mod parser;
+pub mod plugins;
+pub mod render;
+pub mod return_type;
+pub mod runtimes;
+pub mod sandbox;
+pub mod search;
+pub mod simulation;
+pub mod stream;
+pub mod trace;
+
+pub use return_type::ReturnType;
+
+pub const DAYS: RangeInclusive<u32> = 1..=25;
+pub const PARTS: [char; 2] = ['a', 'b'];
+pub const DEFAULT_YEAR: u32 = 2022;
+
+pub const INPUTS_DIR: &str = "inputs";
+pub const TEST_SET: &str = "test";
+
 pub fn to_u32(i: &str) -> u32 {
     i.trim().parse::<u32>().unwrap()
 }
 
 pub fn read_file(day: u32) -> String {
-    read_file_path(day, vec!["inputs"])
+    read_year_file(DEFAULT_YEAR, day, None)
 }
 
 pub fn read_test_file(day: u32) -> String {
-    read_file_path(day, vec!["inputs", "test"])
+    read_year_file(DEFAULT_YEAR, day, Some(TEST_SET))
 }
 
-fn read_file_path(day: u32, path: Vec<&str>) -> String {
+pub fn read_set_file(day: u32, set: &str) -> String {
+    read_year_file(DEFAULT_YEAR, day, Some(set))
+}
+
+pub fn read_year_file(year: u32, day: u32, set: Option<&str>) -> String {
+    read_input(&input_path(year, day, set))
+}
+
+pub fn open_year_file(year: u32, day: u32, set: Option<&str>) -> BufReader<File> {
+    // for streaming solvers. Encrypted inputs are decrypted whole, so they
+    // have to be decrypted to disk first
+    let filepath = input_path(year, day, set);
+    if !filepath.exists() && crypto::encrypted_path(&filepath).exists() {
+        panic!(
+            "{} is only available encrypted, run `inputs decrypt --day {}` to stream it",
+            filepath.display(),
+            day
+        );
+    }
+    let f = File::open(&filepath);
+    BufReader::new(f.unwrap_or_else(|_| panic!("could not open input file {}", filepath.display())))
+}
+
+pub fn input_dir(path: Vec<&str>) -> PathBuf {
     let cwd = env::current_dir().unwrap();
-    let filename = format!("day_{}.txt", day);
-    let filepath = path.iter().fold(cwd, |c, x| c.join(x));
-    let f = fs::read_to_string(filepath.join(filename.clone()));
-    f.expect(&format!("could not open input file {}", filename))
+    path.iter().fold(cwd, |c, x| c.join(x))
+}
+
+pub fn set_dir(year: u32, set: Option<&str>) -> PathBuf {
+    // inputs live in `inputs/<year>/[<set>/]`. 2022 predates the year
+    // directories, so it falls back to the original flat `inputs/[<set>/]`
+    let year_str = year.to_string();
+    let mut year_path = vec![INPUTS_DIR, &year_str];
+    let mut flat_path = vec![INPUTS_DIR];
+    if let Some(set) = set {
+        year_path.push(set);
+        flat_path.push(set);
+    }
+    let year_dir = input_dir(year_path);
+    let flat_dir = input_dir(flat_path);
+    if year == DEFAULT_YEAR && !year_dir.exists() && flat_dir.exists() {
+        flat_dir
+    } else {
+        year_dir
+    }
+}
+
+pub fn input_path(year: u32, day: u32, set: Option<&str>) -> PathBuf {
+    set_dir(year, set).join(format!("day_{}.txt", day))
+}
+
+pub fn input_exists(year: u32, day: u32, set: Option<&str>) -> bool {
+    // an input is available if either its plaintext or encrypted copy exists
+    let filepath = input_path(year, day, set);
+    filepath.exists() || crypto::encrypted_path(&filepath).exists()
+}
+
+fn is_year(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_digit())
+}
+
+fn subdirectories(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn input_sets(year: u32) -> Vec<String> {
+    // every directory of a year's inputs other than the test inputs is a named
+    // set, typically one per AoC account
+    let mut sets = subdirectories(&input_dir(vec![INPUTS_DIR, &year.to_string()]));
+    if year == DEFAULT_YEAR {
+        sets.extend(subdirectories(&input_dir(vec![INPUTS_DIR])));
+    }
+    sets.retain(|name| name != TEST_SET && !is_year(name));
+    sets.sort();
+    sets.dedup();
+    sets
+}
+
+fn read_input(filepath: &Path) -> String {
+    if !filepath.exists() && crypto::encrypted_path(filepath).exists() {
+        return read_encrypted_file(filepath);
+    }
+    let f = fs::read_to_string(filepath);
+    f.unwrap_or_else(|_| panic!("could not open input file {}", filepath.display()))
+}
+
+fn read_encrypted_file(plaintext_path: &Path) -> String {
+    // puzzle inputs can't be published, so they are committed encrypted and
+    // decrypted on the fly using the local key file
+    let encrypted_path = crypto::encrypted_path(plaintext_path);
+    let data = fs::read(&encrypted_path).unwrap();
+    let decrypted = crypto::load_key(&crypto::key_path())
+        .and_then(|key| crypto::decrypt(&key, &data))
+        .unwrap_or_else(|e| panic!("could not decrypt {}: {}", encrypted_path.display(), e));
+    String::from_utf8(decrypted).unwrap()
 }
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// a structural problem with a puzzle input. Lines and columns are 0-based
+// here and shown 1-based, the way editors number them
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Problem {
+        Problem {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+pub fn format_problem(source: &str, input: &str, problem: &Problem) -> String {
+    // problems past the last line (e.g. a missing section) point at an empty line
+    let line = input.lines().nth(problem.line).unwrap_or("");
+    format!(
+        "{}:{}:{}: {}\n    {}\n    {}^",
+        source,
+        problem.line + 1,
+        problem.column + 1,
+        problem.message,
+        line,
+        " ".repeat(problem.column)
+    )
+}
+
+pub fn fields(line: &str) -> Vec<(usize, &str)> {
+    // whitespace separated fields along with the column they start at
+    let mut fields = Vec::new();
+    let mut start = None;
+    for (idx, c) in line.char_indices() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(idx),
+            (true, Some(s)) => {
+                fields.push((s, &line[s..idx]));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    if let Some(s) = start {
+        fields.push((s, &line[s..]));
+    }
+    fields
+}
+
+pub fn sections(input: &str) -> Vec<(usize, Vec<&str>)> {
+    // blank line separated blocks of lines, along with the line each starts on
+    let mut sections: Vec<(usize, Vec<&str>)> = Vec::new();
+    let mut current: Option<(usize, Vec<&str>)> = None;
+    for (idx, line) in input.lines().enumerate() {
+        match (line.is_empty(), current.as_mut()) {
+            (true, _) => sections.extend(current.take()),
+            (false, Some((_, lines))) => lines.push(line),
+            (false, None) => current = Some((idx, vec![line])),
+        }
+    }
+    sections.extend(current);
+    sections
+}
+
+pub fn check_number<T: FromStr>(line: usize, column: usize, value: &str) -> Option<Problem> {
+    value.parse::<T>().err().map(|_| {
+        Problem::new(
+            line,
+            column,
+            format!("expected a number, found `{}`", value),
+        )
+    })
+}
+
+pub fn check_prefix(line: usize, text: &str, prefix: &str) -> Option<Problem> {
+    // points at the first character that differs from the expected prefix
+    if text.starts_with(prefix) {
+        return None;
+    }
+    let column = text
+        .chars()
+        .zip(prefix.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    Some(Problem::new(
+        line,
+        column,
+        format!("expected `{}`", prefix.trim()),
+    ))
+}
+
+pub fn check_not_empty(input: &str) -> Option<Problem> {
+    if input.trim().is_empty() {
+        Some(Problem::new(0, 0, "input is empty"))
+    } else {
+        None
+    }
+}
+
+pub fn check_grid(input: &str, is_cell: impl Fn(char) -> bool, expected: &str) -> Vec<Problem> {
+    // every row must be as wide as the others and only contain valid cells.
+    // The most common width is taken as correct, so a single mangled row
+    // is reported rather than every row but that one
+    if let Some(problem) = check_not_empty(input) {
+        return vec![problem];
+    }
+    let mut width_counts: HashMap<usize, usize> = HashMap::new();
+    for row in input.lines() {
+        *width_counts.entry(row.chars().count()).or_default() += 1;
+    }
+    let width = width_counts
+        .into_iter()
+        .max_by_key(|(width, count)| (*count, *width))
+        .unwrap()
+        .0;
+    let mut problems = Vec::new();
+    for (y, row) in input.lines().enumerate() {
+        let row_width = row.chars().count();
+        if row_width != width {
+            problems.push(Problem::new(
+                y,
+                row_width.min(width),
+                format!(
+                    "row is {} wide, expected {} like the other rows",
+                    row_width, width
+                ),
+            ));
+        }
+        for (x, c) in row.chars().enumerate().filter(|(_, c)| !is_cell(*c)) {
+            problems.push(Problem::new(
+                y,
+                x,
+                format!("unexpected `{}`, expected {}", c, expected),
+            ));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_problem() {
+        let problem = Problem::new(1, 2, "unexpected `x`");
+        assert_eq!(
+            format_problem("day_8.txt", "123\n12x\n", &problem),
+            "day_8.txt:2:3: unexpected `x`\n    12x\n      ^"
+        );
+    }
+
+    #[test]
+    fn test_fields_and_sections() {
+        assert_eq!(fields(" ab  c"), vec![(1, "ab"), (5, "c")]);
+        assert_eq!(
+            sections("a\nb\n\n\nc\n"),
+            vec![(0, vec!["a", "b"]), (4, vec!["c"])]
+        );
+    }
+
+    #[test]
+    fn test_check_grid() {
+        let problems = check_grid("123\n12\n1x3", |c| c.is_ascii_digit(), "a digit");
+        assert_eq!(
+            problems,
+            vec![
+                Problem::new(1, 2, "row is 2 wide, expected 3 like the other rows"),
+                Problem::new(2, 1, "unexpected `x`, expected a digit"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_prefix() {
+        assert_eq!(check_prefix(0, "move 1", "move "), None);
+        assert_eq!(
+            check_prefix(3, "mvoe 1", "move "),
+            Some(Problem::new(3, 1, "expected `move`"))
+        );
+    }
+}
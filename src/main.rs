@@ -1,98 +1,570 @@
 extern crate argparse;
 
-use advent_of_code_2022::read_file;
-use argparse::{ArgumentParser, Store};
-use days::*;
-mod days;
+use advent_of_code_2022::alloc_counter::CountingAllocator;
+use advent_of_code_2022::animation::{self, DEFAULT_FPS};
+use advent_of_code_2022::answers::Answers;
+use advent_of_code_2022::arithmetic;
+use advent_of_code_2022::checks::Checks;
+use advent_of_code_2022::days::{self, Options, SolveError};
+use advent_of_code_2022::render::DEFAULT_SCALE;
+use advent_of_code_2022::runtimes::{format_runtime, Runtimes};
+use advent_of_code_2022::sandbox::{self, Limits, SandboxError};
+use advent_of_code_2022::{
+    input_sets, open_year_file, read_year_file, set_dir, DAYS, DEFAULT_YEAR, PARTS,
+};
+use advent_of_code_2022::{trace, ReturnType};
+use argparse::{ArgumentParser, List, Store, StoreTrue};
+mod commands;
+use serde::{Deserialize, Serialize};
+use std::env;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
 
-#[derive(Debug)]
-enum ReturnType {
-    UInt32(u32),
-    Int64(i64),
-    String(String),
+const ALL_SETS: &str = "all";
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Serialize)]
+struct RunOutput<'a> {
+    year: u32,
+    day: u32,
+    part: char,
+    set: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    answer: Option<&'a ReturnType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    check: Option<String>,
+    // why the part has no answer, e.g. `overflow`, `ocr` or `oom`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_message: Option<String>,
+    // the solver's error itself, so a sandboxed run can pass it back as it was
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solve_error: Option<&'a SolveError>,
+    // the input is parsed once per set, so every part of a set shares this
+    parse_micros: u128,
+    solve_micros: u128,
 }
 
-impl From<u32> for ReturnType {
-    fn from(u: u32) -> Self {
-        Self::UInt32(u)
-    }
+// the parts of a sandboxed child's `RunOutput` the parent needs. It's read
+// straight from the line, as going through `serde_json::Value` would lose
+// the precision of 128 bit answers
+#[derive(Deserialize)]
+struct ChildOutput {
+    answer: Option<ReturnType>,
+    solve_error: Option<SolveError>,
+    #[serde(default)]
+    solve_micros: u64,
 }
 
-impl From<i64> for ReturnType {
-    fn from(i: i64) -> Self {
-        Self::Int64(i)
-    }
+// why a part stopped without an answer
+enum RunError {
+    Solve(SolveError),
+    Sandbox(SandboxError),
 }
 
-impl From<String> for ReturnType {
-    fn from(s: String) -> Self {
-        Self::String(s)
+impl RunError {
+    // a short name for JSON output
+    fn kind(&self) -> &'static str {
+        match self {
+            RunError::Solve(error) => error.kind(),
+            RunError::Sandbox(error) => error.kind(),
+        }
     }
 }
 
-impl fmt::Display for ReturnType {
+impl fmt::Display for RunError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ReturnType::UInt32(value) => write!(f, "{}", value),
-            ReturnType::Int64(value) => write!(f, "{}", value),
-            ReturnType::String(value) => write!(f, "{}", value),
+            RunError::Solve(error) => write!(f, "{}", error),
+            RunError::Sandbox(error) => write!(f, "{}", error),
         }
     }
 }
 
 fn main() {
+    let mut year = DEFAULT_YEAR;
     let mut day = 0;
     let mut part = ' ';
+    let mut set = String::new();
+    let mut check = false;
+    let mut save_answer = false;
+    let mut json = false;
+    let mut no_ocr = false;
+    let mut checked = false;
+    let mut stream = false;
+    let mut sandbox = false;
+    let mut memory_limit = sandbox::DEFAULT_MEMORY_MB;
+    let mut cpu_limit = sandbox::DEFAULT_CPU_SECONDS;
+    let mut explain: u8 = 0;
+    let mut render = String::new();
+    let mut animate = false;
+    let mut fps: u32 = DEFAULT_FPS;
+    let mut explain_format = String::from("text");
+    let mut subcommand = String::new();
+    let mut subcommand_args: Vec<String> = Vec::new();
     {
         // this block limits scope of borrows by ap.refer() method
         let mut ap = ArgumentParser::new();
-        ap.set_description("Run Advent of Code 2022.");
+        ap.set_description("Run Advent of Code solutions.");
+        ap.refer(&mut year)
+            .add_option(&["-y", "--year"], Store, "Year to run, defaults to 2022");
         ap.refer(&mut day)
             .add_option(&["-d", "--day"], Store, "Day to run");
-        ap.refer(&mut part)
-            .add_option(&["-p", "--part"], Store, "Part to run [a, b]");
+        ap.refer(&mut part).add_option(
+            &["-p", "--part"],
+            Store,
+            "Part to run [a, b], both if not given",
+        );
+        ap.refer(&mut set).add_option(
+            &["-s", "--set"],
+            Store,
+            "Input set to run, a directory under `inputs`, or `all` for every set",
+        );
+        ap.refer(&mut check).add_option(
+            &["-c", "--check"],
+            StoreTrue,
+            "Compare the result against the set's expected answer",
+        );
+        ap.refer(&mut save_answer).add_option(
+            &["--save-answer"],
+            StoreTrue,
+            "Record the result as the set's expected answer",
+        );
+        ap.refer(&mut json).add_option(
+            &["-j", "--json"],
+            StoreTrue,
+            "Print each result as a JSON object",
+        );
+        ap.refer(&mut no_ocr).add_option(
+            &["--no-ocr"],
+            StoreTrue,
+            "Return CRT pictures as they are instead of reading the letters",
+        );
+        ap.refer(&mut stream).add_option(
+            &["--stream"],
+            StoreTrue,
+            "Solve while reading the input instead of loading it first (days 1, 2, 3, 4, 6 and 9)",
+        );
+        ap.refer(&mut sandbox).add_option(
+            &["--sandbox"],
+            StoreTrue,
+            "Run each part in a child process with memory and CPU limits",
+        );
+        ap.refer(&mut memory_limit).add_option(
+            &["--memory-limit"],
+            Store,
+            "Address space limit in MB for --sandbox, defaults to 2048",
+        );
+        ap.refer(&mut cpu_limit).add_option(
+            &["--cpu-limit"],
+            Store,
+            "CPU time limit in seconds for --sandbox, defaults to 60",
+        );
+        ap.refer(&mut checked).add_option(
+            &["--checked"],
+            StoreTrue,
+            "Check solver arithmetic and report overflows instead of wrapping",
+        );
+        ap.refer(&mut explain).add_option(
+            &["-e", "--explain"],
+            Store,
+            "Narrate the solver's steps to stderr: 1 for steps and decisions, 2 adds state",
+        );
+        ap.refer(&mut explain_format).add_option(
+            &["--explain-format"],
+            Store,
+            "Format of explain output [text, json]",
+        );
+        ap.refer(&mut render).add_option(
+            &["-r", "--render"],
+            Store,
+            "Render the day's puzzle state to an image [.ppm, .png, .svg]",
+        );
+        ap.refer(&mut animate).add_option(
+            &["-a", "--animate"],
+            StoreTrue,
+            "Animate the simulation in the terminal (days 5, 9, 10 and 12)",
+        );
+        ap.refer(&mut fps)
+            .add_option(&["--fps"], Store, "Frames per second for --animate");
+        ap.refer(&mut subcommand).add_argument(
+            "command",
+            Store,
+            "Optional subcommand [inputs, examples, status, dashboard, leaderboard, report, lint]",
+        );
+        ap.refer(&mut subcommand_args).add_argument(
+            "arguments",
+            List,
+            "Arguments for the subcommand",
+        );
+        ap.stop_on_first_argument(true);
         ap.parse_args_or_exit();
     }
 
-    if day < 1 || day > 25 {
+    if !subcommand.is_empty() {
+        subcommand_args.insert(0, subcommand.clone());
+        match subcommand.as_str() {
+            "inputs" => commands::inputs::run(subcommand_args),
+            "examples" => commands::examples::run(subcommand_args),
+            "status" => commands::status::run(subcommand_args),
+            "dashboard" => commands::dashboard::run(subcommand_args),
+            "leaderboard" => commands::leaderboard::run(subcommand_args),
+            "report" => commands::report::run(subcommand_args),
+            "lint" => commands::lint::run(subcommand_args),
+            _ => panic!("Unrecognised command [{}]", subcommand),
+        }
+        return;
+    }
+
+    // only running a day can use a plugin
+    days::load_plugins();
+    if !days::years().contains(&year) {
+        panic!(
+            "--year parameter must be one of {:?}, no solutions are registered for [{}]",
+            days::years(),
+            year
+        );
+    }
+    if !DAYS.contains(&day) {
         panic!("--day parameter must be specified and be between `1` and `25` inclusive");
     }
 
-    let valid_parts = vec!['a', 'b'];
-    if !valid_parts.contains(&part) {
-        panic!("--part parameter must be specified and be set either `a` or `b`");
-    }
-
-    let input = read_file(day);
-
-    let result: ReturnType = match (day, part) {
-        (1, 'a') => day_1::part_a(&input).into(),
-        (1, 'b') => day_1::part_b(&input).into(),
-        (2, 'a') => day_2::part_a(&input).into(),
-        (2, 'b') => day_2::part_b(&input).into(),
-        (3, 'a') => day_3::part_a(&input).into(),
-        (3, 'b') => day_3::part_b(&input).into(),
-        (4, 'a') => day_4::part_a(&input).into(),
-        (4, 'b') => day_4::part_b(&input).into(),
-        (5, 'a') => day_5::part_a(&input).into(),
-        (5, 'b') => day_5::part_b(&input).into(),
-        (6, 'a') => day_6::part_a(&input).into(),
-        (6, 'b') => day_6::part_b(&input).into(),
-        (7, 'a') => day_7::part_a(&input).into(),
-        (7, 'b') => day_7::part_b(&input).into(),
-        (8, 'a') => day_8::part_a(&input).into(),
-        (8, 'b') => day_8::part_b(&input).into(),
-        (9, 'a') => day_9::part_a(&input).into(),
-        (9, 'b') => day_9::part_b(&input).into(),
-        (10, 'a') => day_10::part_a(&input).into(),
-        (10, 'b') => day_10::part_b(&input).into(),
-        (11, 'a') => day_11::part_a(&input).into(),
-        (11, 'b') => day_11::part_b(&input).into(),
-        (12, 'a') => day_12::part_a(&input).into(),
-        (12, 'b') => day_12::part_b(&input).into(),
-        (_, _) => panic!("Unrecognised day [{}] part [{}]", day, part),
+    let solution = days::find(year, day)
+        .unwrap_or_else(|| panic!("Unrecognised year [{}] day [{}]", year, day));
+    let parts: Vec<char> = match part {
+        ' ' => PARTS
+            .into_iter()
+            .filter(|p| solution.part(*p).is_some())
+            .collect(),
+        'a' | 'b' => vec![part],
+        _ => panic!("--part parameter must be either `a` or `b`"),
+    };
+    for part in &parts {
+        if solution.part(*part).is_none() {
+            panic!("Unrecognised year [{}] day [{}] part [{}]", year, day, part);
+        }
+    }
+
+    if stream && solution.stream.is_none() {
+        panic!("day [{}] can't be solved with --stream", day);
+    }
+    if stream && !render.is_empty() {
+        panic!("--render needs the whole input, it can't be combined with --stream");
+    }
+
+    if sandbox && animate {
+        panic!("--animate needs the terminal, it can't be combined with --sandbox");
+    }
+    let limits = Limits {
+        memory_mb: memory_limit,
+        cpu_seconds: cpu_limit,
+    };
+
+    let options = Options { ocr: !no_ocr };
+    arithmetic::set_checked(checked);
+
+    match explain_format.as_str() {
+        "text" => trace::set_explain(explain, false),
+        "json" => trace::set_explain(explain, true),
+        _ => panic!("--explain-format must be either `text` or `json`"),
+    }
+
+    let sets: Vec<Option<String>> = match set.as_str() {
+        "" => vec![None],
+        ALL_SETS => input_sets(year).into_iter().map(Some).collect(),
+        name => vec![Some(name.to_string())],
     };
+    if sets.is_empty() {
+        panic!("--set all was given but there are no input sets in `inputs`");
+    }
+
+    let mut all_correct = true;
+    for set in sets {
+        let dir = set_dir(year, set.as_deref());
+        let label = match &set {
+            None => String::new(),
+            Some(name) => format!(" [{}]", name),
+        };
+
+        // streaming and sandboxed solvers read the input themselves, once per
+        // part, so there's no separate parse step
+        let input = (!stream && !sandbox || !render.is_empty())
+            .then(|| read_year_file(year, day, set.as_deref()));
+
+        if let (false, Some(input)) = (render.is_empty(), &input) {
+            let path = render_path(&render, &set);
+            render_day(year, day, input, &path);
+            println!("Rendered{} to {}", label, path.display());
+        }
 
-    println!("Result: {}", result)
+        let start = Instant::now();
+        let parsed = input
+            .as_deref()
+            .filter(|_| !stream && !sandbox)
+            .map(solution.parse);
+        let parse_time = start.elapsed();
+        if !json && parsed.is_some() {
+            println!("Parsed{} in {}", label, format_runtime(&parse_time));
+        }
+
+        for part in &parts {
+            let part = *part;
+            if animate {
+                animation::start(fps);
+            }
+            let name = if parts.len() > 1 {
+                format!("Part {}", part)
+            } else {
+                "Result".to_string()
+            };
+            let outcome = if sandbox {
+                let child = SandboxedRun {
+                    year,
+                    day,
+                    part,
+                    set: set.as_deref(),
+                    stream,
+                    checked,
+                    no_ocr,
+                    explain,
+                    explain_format: &explain_format,
+                };
+                child.run(limits)
+            } else {
+                let start = Instant::now();
+                let result = match &parsed {
+                    Some(parsed) => solution.part(part).unwrap().solve(parsed.as_ref(), options),
+                    None => {
+                        let mut reader = open_year_file(year, day, set.as_deref());
+                        (solution.stream.unwrap())(part, &mut reader)
+                    }
+                };
+                let solve_time = start.elapsed();
+                result
+                    .map(|answer| (answer, solve_time))
+                    .map_err(RunError::Solve)
+            };
+            animation::finish();
+            let (result, solve_time) = match outcome {
+                Ok(outcome) => outcome,
+                Err(error) => {
+                    // the runner carries on with the other parts and sets
+                    all_correct = false;
+                    if json {
+                        let output = RunOutput {
+                            year,
+                            day,
+                            part,
+                            set: set.as_deref(),
+                            answer: None,
+                            check: None,
+                            error: Some(error.kind()),
+                            error_message: Some(error.to_string()),
+                            solve_error: match &error {
+                                RunError::Solve(error) => Some(error),
+                                RunError::Sandbox(_) => None,
+                            },
+                            parse_micros: 0,
+                            solve_micros: 0,
+                        };
+                        println!("{}", serde_json::to_string(&output).unwrap());
+                    } else {
+                        println!("{}{}: {}", name, label, error);
+                    }
+                    continue;
+                }
+            };
+            if set.is_none() && !sandbox && !animate && explain == 0 {
+                // status reports the latest runtime against the real inputs,
+                // which doesn't include parsing. Sandboxed runs record their
+                // own, and animated or explained runs are slowed down by the
+                // frames and trace, so they aren't kept
+                let mut runtimes = Runtimes::load(&env::current_dir().unwrap());
+                runtimes.record(year, day, part, solve_time);
+                runtimes.save();
+            }
+
+            // expected answers are kept per set, next to that set's inputs
+            let verdict = if save_answer {
+                let mut answers = Answers::load(&dir);
+                answers.set(day, part, &result.to_string());
+                answers.save();
+                let mut checks = Checks::load(&dir);
+                checks.forget(day, part);
+                checks.save();
+                Some("saved".to_string())
+            } else if check {
+                // verdicts are kept for status' stars
+                let mut checks = Checks::load(&dir);
+                let verdict = match Answers::load(&dir).get(day, part) {
+                    Some(expected) if result.matches(expected) => {
+                        checks.record(day, part, true);
+                        "correct".to_string()
+                    }
+                    Some(expected) => {
+                        all_correct = false;
+                        checks.record(day, part, false);
+                        format!("incorrect, expected {}", expected)
+                    }
+                    None => "no expected answer".to_string(),
+                };
+                checks.save();
+                Some(verdict)
+            } else {
+                None
+            };
+
+            if json {
+                let output = RunOutput {
+                    year,
+                    day,
+                    part,
+                    set: set.as_deref(),
+                    answer: Some(&result),
+                    check: verdict,
+                    error: None,
+                    error_message: None,
+                    solve_error: None,
+                    parse_micros: parse_time.as_micros(),
+                    solve_micros: solve_time.as_micros(),
+                };
+                println!("{}", serde_json::to_string(&output).unwrap());
+                continue;
+            }
+            // grids start on their own line so the rows line up
+            let shown = if result.is_multiline() {
+                format!("\n{}", result)
+            } else {
+                result.to_string()
+            };
+            match verdict {
+                Some(verdict) => println!("{}{}: {} ({})", name, label, shown, verdict),
+                None => println!("{}{}: {}", name, label, shown),
+            }
+        }
+    }
+
+    if !all_correct {
+        std::process::exit(1);
+    }
+}
+
+// the arguments a sandboxed child needs to solve one part the same way
+struct SandboxedRun<'a> {
+    year: u32,
+    day: u32,
+    part: char,
+    set: Option<&'a str>,
+    stream: bool,
+    checked: bool,
+    no_ocr: bool,
+    explain: u8,
+    explain_format: &'a str,
+}
+
+impl SandboxedRun<'_> {
+    fn run(&self, limits: Limits) -> Result<(ReturnType, Duration), RunError> {
+        let mut command = Command::new(env::current_exe().unwrap());
+        command.args([
+            "--year",
+            &self.year.to_string(),
+            "--day",
+            &self.day.to_string(),
+            "--part",
+            &self.part.to_string(),
+            "--explain",
+            &self.explain.to_string(),
+            "--explain-format",
+            self.explain_format,
+            "--json",
+        ]);
+        if let Some(set) = self.set {
+            command.args(["--set", set]);
+        }
+        for (flag, given) in [
+            ("--stream", self.stream),
+            ("--checked", self.checked),
+            ("--no-ocr", self.no_ocr),
+        ] {
+            if given {
+                command.arg(flag);
+            }
+        }
+        let stdout = sandbox::run(command, limits).map_err(RunError::Sandbox)?;
+        read_child_output(&stdout)
+    }
+}
+
+fn read_child_output(stdout: &str) -> Result<(ReturnType, Duration), RunError> {
+    // the child answers with a single `RunOutput` line
+    let crashed = |message| RunError::Sandbox(SandboxError::Crashed(message));
+    let line = stdout
+        .lines()
+        .last()
+        .ok_or_else(|| crashed(format!("unexpected output {:?}", stdout)))?;
+    let output: ChildOutput = serde_json::from_str(line)
+        .map_err(|e| crashed(format!("unreadable output {:?}: {}", line, e)))?;
+    match output {
+        ChildOutput {
+            solve_error: Some(error),
+            ..
+        } => Err(RunError::Solve(error)),
+        ChildOutput {
+            answer: Some(answer),
+            solve_micros,
+            ..
+        } => Ok((answer, Duration::from_micros(solve_micros))),
+        _ => Err(crashed(format!("no answer in {:?}", line))),
+    }
+}
+
+fn render_path(render: &str, set: &Option<String>) -> PathBuf {
+    // with several sets each one gets its own image, e.g. `out.alice.png`
+    let path = PathBuf::from(render);
+    match set {
+        None => path,
+        Some(name) => {
+            let stem = path.file_stem().unwrap().to_string_lossy();
+            let extension = path.extension().unwrap_or_default().to_string_lossy();
+            path.with_file_name(format!("{}.{}.{}", stem, name, extension))
+        }
+    }
+}
+
+fn render_day(year: u32, day: u32, input: &str, path: &Path) {
+    let renderer = days::find(year, day)
+        .and_then(|s| s.render)
+        .unwrap_or_else(|| panic!("day [{}] has no renderer", day));
+    renderer(input).write(path, DEFAULT_SCALE);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_output_keeps_128_bit_answers() {
+        let answer = ReturnType::UInt128(u64::MAX as u128 * 3);
+        let output = RunOutput {
+            year: 2022,
+            day: 11,
+            part: 'b',
+            set: None,
+            answer: Some(&answer),
+            check: None,
+            error: None,
+            error_message: None,
+            solve_error: None,
+            parse_micros: 0,
+            solve_micros: 12,
+        };
+        let line = serde_json::to_string(&output).unwrap();
+        let (read, solve_time) = read_child_output(&line).ok().unwrap();
+        assert_eq!((read, solve_time), (answer, Duration::from_micros(12)));
+    }
 }
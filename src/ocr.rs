@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// puzzles that draw letters on a CRT (2022 day 10, 2016 day 8, 2019 day 11...)
+// use one of two block letter fonts. Lit pixels are `#`, anything else is dark
+const LIT: char = '#';
+
+// letters are 4 wide with a 1 column gap, except `Y` which fills the gap
+const SMALL_HEIGHT: usize = 6;
+const SMALL_PITCH: usize = 5;
+const SMALL_FONT: [(char, [&str; SMALL_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// letters are 6 wide with a 2 column gap
+const LARGE_HEIGHT: usize = 10;
+const LARGE_PITCH: usize = 8;
+#[rustfmt::skip]
+const LARGE_FONT: [(char, [&str; LARGE_HEIGHT]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OcrError {
+    UnsupportedHeight(usize),
+    UnrecognisedGlyph { column: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::UnsupportedHeight(height) => write!(
+                f,
+                "pictures must be {} or {} rows tall to read, this one is {}",
+                SMALL_HEIGHT, LARGE_HEIGHT, height
+            ),
+            OcrError::UnrecognisedGlyph { column, glyph } => write!(
+                f,
+                "unrecognised glyph at column {}, run with --no-ocr to see the picture\n{}",
+                column + 1,
+                glyph
+            ),
+        }
+    }
+}
+
+fn glyph(picture: &[Vec<char>], column: usize, width: usize) -> Vec<String> {
+    // pixels past the right edge of the picture are dark
+    picture
+        .iter()
+        .map(|row| {
+            (column..column + width)
+                .map(|x| if row.get(x) == Some(&LIT) { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+fn read_with<const H: usize>(
+    picture: &[Vec<char>],
+    font: &[(char, [&str; H])],
+    pitch: usize,
+) -> Result<String, OcrError> {
+    // letters are matched at their own width, errors show the usual width
+    let width = font[0].1[0].len();
+    let picture_width = picture.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut letters = String::new();
+    for column in (0..picture_width).step_by(pitch) {
+        let pixels = glyph(picture, column, width);
+        if pixels.iter().all(|row| !row.contains(LIT)) {
+            letters.push(' ');
+            continue;
+        }
+        let letter = font
+            .iter()
+            .find(|(_, rows)| *rows == glyph(picture, column, rows[0].len())[..])
+            .map(|(letter, _)| *letter)
+            .ok_or_else(|| OcrError::UnrecognisedGlyph {
+                column,
+                glyph: pixels.join("\n"),
+            })?;
+        letters.push(letter);
+    }
+    Ok(letters.trim().to_string())
+}
+
+pub fn read(picture: &[Vec<char>]) -> Result<String, OcrError> {
+    match picture.len() {
+        SMALL_HEIGHT => read_with(picture, &SMALL_FONT, SMALL_PITCH),
+        LARGE_HEIGHT => read_with(picture, &LARGE_FONT, LARGE_PITCH),
+        height => Err(OcrError::UnsupportedHeight(height)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picture(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|row| row.chars().collect()).collect()
+    }
+
+    #[test]
+    fn test_read_small_font() {
+        let rows = [
+            "#..#.####.#....#.....##..",
+            "#..#.#....#....#....#..#.",
+            "####.###..#....#....#..#.",
+            "#..#.#....#....#....#..#.",
+            "#..#.#....#....#....#..#.",
+            "#..#.####.####.####..##..",
+        ];
+        assert_eq!(read(&picture(&rows)), Ok("HELLO".to_string()));
+    }
+
+    #[test]
+    fn test_read_large_font() {
+        let mut rows: Vec<String> = LARGE_FONT[10].1.iter().map(|r| r.to_string()).collect();
+        for (row, x) in rows.iter_mut().zip(LARGE_FONT[13].1) {
+            row.push_str("..");
+            row.push_str(x);
+        }
+        let rows: Vec<&str> = rows.iter().map(|r| r.as_str()).collect();
+        assert_eq!(read(&picture(&rows)), Ok("NX".to_string()));
+    }
+
+    fn read_font<const H: usize>(font: &[(char, [&str; H])], pitch: usize) {
+        // every letter in a row, each padded out to the pitch
+        let rows: Vec<String> = (0..H)
+            .map(|y| {
+                font.iter()
+                    .map(|(_, rows)| format!("{:.<pitch$}", rows[y]))
+                    .collect()
+            })
+            .collect();
+        let rows: Vec<&str> = rows.iter().map(|r| r.as_str()).collect();
+        let letters: String = font.iter().map(|(letter, _)| *letter).collect();
+        assert_eq!(read(&picture(&rows)), Ok(letters));
+    }
+
+    #[test]
+    fn test_read_every_letter() {
+        read_font(&SMALL_FONT, SMALL_PITCH);
+        read_font(&LARGE_FONT, LARGE_PITCH);
+    }
+
+    #[test]
+    fn test_read_i_and_y() {
+        let rows = [
+            ".###.#...#",
+            "..#..#...#",
+            "..#...#.#.",
+            "..#....#..",
+            "..#....#..",
+            ".###...#..",
+        ];
+        assert_eq!(read(&picture(&rows)), Ok("IY".to_string()));
+    }
+
+    #[test]
+    fn test_unrecognised_glyph() {
+        let rows = ["####", "####", "####", "####", "####", "####"];
+        assert_eq!(
+            read(&picture(&rows)),
+            Err(OcrError::UnrecognisedGlyph {
+                column: 0,
+                glyph: rows.join("\n")
+            })
+        );
+        assert_eq!(read(&picture(&["#"])), Err(OcrError::UnsupportedHeight(1)));
+    }
+}
@@ -0,0 +1,428 @@
+use std::fmt;
+use std::str::FromStr;
+
+// a small parser combinator toolkit for puzzle inputs. A parser takes the
+// remaining input and returns what it read with the input left after it. Every
+// slice a parser sees is part of the original input, which is how errors find
+// their line and column
+pub type PResult<'a, T> = Result<(T, &'a str), Failure<'a>>;
+
+pub trait Parser<'a, T>: Fn(&'a str) -> PResult<'a, T> {}
+
+impl<'a, T, F: Fn(&'a str) -> PResult<'a, T>> Parser<'a, T> for F {}
+
+// where a parser gave up and what it wanted to see there
+#[derive(Debug, PartialEq)]
+pub struct Failure<'a> {
+    pub at: &'a str,
+    pub expected: String,
+}
+
+impl<'a> Failure<'a> {
+    pub fn new(at: &'a str, expected: impl Into<String>) -> Failure<'a> {
+        Failure {
+            at,
+            expected: expected.into(),
+        }
+    }
+}
+
+// a failure resolved against the whole input. Lines and columns are 0-based
+// like `lint::Problem`, and shown 1-based
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {}",
+            self.line + 1,
+            self.column + 1,
+            self.expected,
+            self.found
+        )
+    }
+}
+
+impl ParseError {
+    fn new(input: &str, failure: Failure) -> ParseError {
+        // `at` is a suffix of some slice of `input`, so its start is an offset
+        // into `input`
+        let offset = (failure.at.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let found = match failure.at.lines().next() {
+            Some(text) if !text.is_empty() => format!("`{}`", text),
+            _ if failure.at.is_empty() => "the end of the input".to_string(),
+            _ => "the end of the line".to_string(),
+        };
+        ParseError {
+            line: before.matches('\n').count(),
+            column: before[line_start..].chars().count(),
+            expected: failure.expected,
+            found,
+        }
+    }
+}
+
+pub fn parse_all<'a, T>(parser: impl Parser<'a, T>, input: &'a str) -> Result<T, ParseError> {
+    // the whole input must be read, apart from trailing blank lines
+    match parser(input) {
+        Ok((value, rest)) if rest.trim_end_matches('\n').is_empty() => Ok(value),
+        Ok((_, rest)) => Err(ParseError::new(
+            input,
+            Failure::new(rest, "the end of the input"),
+        )),
+        Err(failure) => Err(ParseError::new(input, failure)),
+    }
+}
+
+pub fn expect_parse<'a, T>(parser: impl Parser<'a, T>, input: &'a str) -> T {
+    // for solvers, which panic on bad input
+    parse_all(parser, input).unwrap_or_else(|e| panic!("invalid input at {}", e))
+}
+
+// primitives
+
+pub fn literal<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(expected) {
+        Some(rest) => Ok((&input[..expected.len()], rest)),
+        None => Err(Failure::new(input, format!("`{}`", expected))),
+    }
+}
+
+pub fn take_while1<'a>(
+    predicate: impl Fn(char) -> bool,
+    expected: &'static str,
+) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| {
+        let end = input
+            .char_indices()
+            .find(|(_, c)| !predicate(*c))
+            .map_or(input.len(), |(i, _)| i);
+        if end == 0 {
+            return Err(Failure::new(input, expected));
+        }
+        Ok((&input[..end], &input[end..]))
+    }
+}
+
+pub fn satisfy<'a>(
+    predicate: impl Fn(char) -> bool,
+    expected: &'static str,
+) -> impl Parser<'a, char> {
+    // a single character
+    move |input: &'a str| match input.chars().next() {
+        Some(c) if predicate(c) => Ok((c, &input[c.len_utf8()..])),
+        _ => Err(Failure::new(input, expected)),
+    }
+}
+
+pub fn one_of<'a>(chars: &'static str) -> impl Parser<'a, char> {
+    move |input: &'a str| match input.chars().next() {
+        Some(c) if chars.contains(c) => Ok((c, &input[c.len_utf8()..])),
+        _ => {
+            let options: Vec<String> = chars.chars().map(|c| format!("`{}`", c)).collect();
+            let expected = match options.split_last() {
+                Some((last, [])) => last.clone(),
+                Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+                None => "nothing".to_string(),
+            };
+            Err(Failure::new(input, expected))
+        }
+    }
+}
+
+pub fn integer<'a, T: FromStr>() -> impl Parser<'a, T> {
+    // an optional `-` then digits, which must fit in T
+    move |input: &'a str| {
+        let digits_start = usize::from(input.starts_with('-'));
+        let digits = input[digits_start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(input.len() - digits_start);
+        if digits == 0 {
+            return Err(Failure::new(input, "a number"));
+        }
+        let end = digits_start + digits;
+        match input[..end].parse::<T>() {
+            Ok(value) => Ok((value, &input[end..])),
+            Err(_) => Err(Failure::new(
+                input,
+                format!("a number that fits in {}", std::any::type_name::<T>()),
+            )),
+        }
+    }
+}
+
+pub fn spaces<'a>() -> impl Parser<'a, &'a str> {
+    // zero or more spaces, never fails
+    move |input: &'a str| {
+        let rest = input.trim_start_matches(' ');
+        Ok((&input[..input.len() - rest.len()], rest))
+    }
+}
+
+pub fn rest_of_line<'a>() -> impl Parser<'a, &'a str> {
+    // everything up to the end of the line, which must not be empty
+    move |input: &'a str| {
+        let end = input.find('\n').unwrap_or(input.len());
+        if end == 0 {
+            return Err(Failure::new(input, "some text"));
+        }
+        Ok((&input[..end], &input[end..]))
+    }
+}
+
+// combinators
+
+pub fn map<'a, A, B>(parser: impl Parser<'a, A>, f: impl Fn(A) -> B) -> impl Parser<'a, B> {
+    move |input: &'a str| parser(input).map(|(value, rest)| (f(value), rest))
+}
+
+pub fn value<'a, A, B: Clone>(parser: impl Parser<'a, A>, value: B) -> impl Parser<'a, B> {
+    move |input: &'a str| parser(input).map(|(_, rest)| (value.clone(), rest))
+}
+
+pub fn pair<'a, A, B>(
+    first: impl Parser<'a, A>,
+    second: impl Parser<'a, B>,
+) -> impl Parser<'a, (A, B)> {
+    move |input: &'a str| {
+        let (a, rest) = first(input)?;
+        let (b, rest) = second(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+pub fn separated_pair<'a, A, S, B>(
+    first: impl Parser<'a, A>,
+    separator: impl Parser<'a, S>,
+    second: impl Parser<'a, B>,
+) -> impl Parser<'a, (A, B)> {
+    move |input: &'a str| {
+        let (a, rest) = first(input)?;
+        let (_, rest) = separator(rest)?;
+        let (b, rest) = second(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+pub fn preceded<'a, A, B>(
+    prefix: impl Parser<'a, A>,
+    parser: impl Parser<'a, B>,
+) -> impl Parser<'a, B> {
+    move |input: &'a str| {
+        let (_, rest) = prefix(input)?;
+        parser(rest)
+    }
+}
+
+pub fn terminated<'a, A, B>(
+    parser: impl Parser<'a, A>,
+    suffix: impl Parser<'a, B>,
+) -> impl Parser<'a, A> {
+    move |input: &'a str| {
+        let (value, rest) = parser(input)?;
+        let (_, rest) = suffix(rest)?;
+        Ok((value, rest))
+    }
+}
+
+pub fn delimited<'a, A, B, C>(
+    prefix: impl Parser<'a, A>,
+    parser: impl Parser<'a, B>,
+    suffix: impl Parser<'a, C>,
+) -> impl Parser<'a, B> {
+    terminated(preceded(prefix, parser), suffix)
+}
+
+pub fn or<'a, T>(first: impl Parser<'a, T>, second: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    // the second is tried from the same place when the first fails. If both
+    // fail, the one that got furthest is reported
+    move |input: &'a str| match first(input) {
+        Ok(result) => Ok(result),
+        Err(a) => match second(input) {
+            Ok(result) => Ok(result),
+            Err(b) if a.at.len() == b.at.len() => Err(Failure::new(
+                a.at,
+                format!("{} or {}", a.expected, b.expected),
+            )),
+            Err(b) if b.at.len() < a.at.len() => Err(b),
+            Err(_) => Err(a),
+        },
+    }
+}
+
+// `alt!(a, b, c)` tries each parser in turn
+#[macro_export]
+macro_rules! alt {
+    ($first:expr $(,)?) => { $first };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        $crate::parser::or($first, $crate::alt!($($rest),+))
+    };
+}
+
+pub fn optional<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |input: &'a str| match parser(input) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(_) => Ok((None, input)),
+    }
+}
+
+pub fn many<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    // zero or more, stopping at the first failure or when nothing is consumed
+    move |input: &'a str| {
+        let mut values = Vec::new();
+        let mut rest = input;
+        while let Ok((value, next)) = parser(rest) {
+            if next.len() == rest.len() {
+                break;
+            }
+            values.push(value);
+            rest = next;
+        }
+        Ok((values, rest))
+    }
+}
+
+pub fn separated<'a, T, S>(
+    item: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    // one or more items. A separator not followed by an item is left unread
+    move |input: &'a str| {
+        let (first, mut rest) = item(input)?;
+        let mut values = vec![first];
+        while let Ok((value, next)) = preceded(&separator, &item)(rest) {
+            values.push(value);
+            rest = next;
+        }
+        Ok((values, rest))
+    }
+}
+
+// framing
+
+fn end_of<'a>(rest: &'a str, what: &'static str) -> Result<(), Failure<'a>> {
+    if rest.trim_end_matches('\n').is_empty() {
+        Ok(())
+    } else {
+        Err(Failure::new(rest, what))
+    }
+}
+
+pub fn line<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    // the parser must read up to the end of the line, the newline is consumed
+    move |input: &'a str| {
+        let (value, rest) = parser(input)?;
+        match rest.strip_prefix('\n') {
+            Some(rest) => Ok((value, rest)),
+            None if rest.is_empty() => Ok((value, rest)),
+            None => Err(Failure::new(rest, "the end of the line")),
+        }
+    }
+}
+
+pub fn lines<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    // every line of the input, each read in full by the parser
+    move |input: &'a str| {
+        let mut values = Vec::new();
+        for text in input.lines() {
+            let (value, rest) = parser(text)?;
+            end_of(rest, "the end of the line")?;
+            values.push(value);
+        }
+        Ok((values, &input[input.len()..]))
+    }
+}
+
+pub fn section<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    // the text up to the next blank line, read in full by the parser. The
+    // blank line is consumed
+    move |input: &'a str| {
+        let (block, rest) = match input.find("\n\n") {
+            Some(end) => (&input[..end], &input[end + 2..]),
+            None => (input, &input[input.len()..]),
+        };
+        let (value, block_rest) = parser(block)?;
+        end_of(block_rest, "a blank line")?;
+        Ok((value, rest))
+    }
+}
+
+pub fn paragraphs<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    // blank line separated sections until the input runs out
+    let section = section(parser);
+    move |input: &'a str| {
+        let mut values = Vec::new();
+        let mut rest = input;
+        while !rest.trim_end_matches('\n').is_empty() {
+            let (value, next) = section(rest)?;
+            values.push(value);
+            rest = next;
+        }
+        Ok((values, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combinators() {
+        let range = separated_pair(integer::<u32>(), literal("-"), integer::<u32>());
+        assert_eq!(parse_all(&range, "2-14"), Ok((2, 14)));
+        assert_eq!(
+            parse_all(separated(integer::<i64>(), literal(", ")), "1, -2, 3"),
+            Ok(vec![1, -2, 3])
+        );
+        let keyword = alt!(
+            value(literal("noop"), 0),
+            preceded(literal("addx "), integer::<i64>())
+        );
+        assert_eq!(
+            parse_all(lines(keyword), "noop\naddx -5\n"),
+            Ok(vec![0, -5])
+        );
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let input = "1\n2\n\n3\n";
+        assert_eq!(
+            parse_all(paragraphs(lines(integer::<u32>())), input),
+            Ok(vec![vec![1, 2], vec![3]])
+        );
+    }
+
+    #[test]
+    fn test_errors_have_positions() {
+        let input = "1-2\n3-x\n";
+        let range = separated_pair(integer::<u32>(), literal("-"), integer::<u32>());
+        assert_eq!(
+            parse_all(lines(range), input),
+            Err(ParseError {
+                line: 1,
+                column: 2,
+                expected: "a number".to_string(),
+                found: "`x`".to_string(),
+            })
+        );
+        let error = parse_all(integer::<u8>(), "300").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected a number that fits in u8, found `300`"
+        );
+        let error = parse_all(or(literal("a"), literal("b")), "c").unwrap_err();
+        assert_eq!(error.expected, "`a` or `b`");
+    }
+}
@@ -0,0 +1,289 @@
+use crate::DAYS;
+use libloading::{Library, Symbol};
+use std::ffi::{c_char, CStr};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{env, fmt, fs};
+
+// plugins are shared libraries (`.so` on Linux) in the plugins directory,
+// which can be pointed elsewhere with the AOC_PLUGINS_DIR env var
+pub const PLUGINS_DIR: &str = "plugins";
+pub const PLUGINS_DIR_ENV_VAR: &str = "AOC_PLUGINS_DIR";
+
+// bump whenever `SolverDescriptor` changes, plugins built against another
+// version are rejected instead of being called with the wrong layout
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+// every plugin exports `extern "C" fn aoc_solver_descriptor() -> *const SolverDescriptor`
+pub const DESCRIPTOR_SYMBOL: &[u8] = b"aoc_solver_descriptor";
+
+pub type SolveFn = extern "C" fn(part: c_char, input: *const u8, input_len: usize) -> *mut c_char;
+pub type FreeAnswerFn = extern "C" fn(answer: *mut c_char);
+type DescriptorFn = extern "C" fn() -> *const SolverDescriptor;
+
+// the directory plugins were loaded from, and the plugins loaded from it
+static PLUGINS: OnceLock<(PathBuf, Vec<Plugin>)> = OnceLock::new();
+
+#[repr(C)]
+pub struct SolverDescriptor {
+    // must stay the first field so it can be checked whatever the version
+    pub abi_version: u32,
+    pub year: u32,
+    pub day: u32,
+    // NUL terminated list of the parts the plugin solves, e.g. "ab"
+    pub parts: *const c_char,
+    // returns a NUL terminated answer, or null on failure. The answer is
+    // handed back to `free_answer` so it is freed by the allocator that made it
+    pub solve: SolveFn,
+    pub free_answer: FreeAnswerFn,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PluginError {
+    Load(PathBuf, String),
+    MissingDescriptor(PathBuf),
+    IncompatibleVersion(PathBuf, u32),
+    InvalidDescriptor(PathBuf, String),
+    // plugins are loaded once, so another directory can't be loaded later
+    AlreadyLoaded(PathBuf, PathBuf),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PluginError::Load(path, e) => write!(f, "could not load {}: {}", path.display(), e),
+            PluginError::MissingDescriptor(path) => write!(
+                f,
+                "{} does not export `{}`",
+                path.display(),
+                String::from_utf8_lossy(DESCRIPTOR_SYMBOL)
+            ),
+            PluginError::IncompatibleVersion(path, version) => write!(
+                f,
+                "{} was built for plugin ABI version {}, expected {}",
+                path.display(),
+                version,
+                PLUGIN_ABI_VERSION
+            ),
+            PluginError::InvalidDescriptor(path, reason) => {
+                write!(
+                    f,
+                    "{} has an invalid descriptor: {}",
+                    path.display(),
+                    reason
+                )
+            }
+            PluginError::AlreadyLoaded(loaded, dir) => write!(
+                f,
+                "could not load {}, plugins were already loaded from {}",
+                dir.display(),
+                loaded.display()
+            ),
+        }
+    }
+}
+
+pub struct Plugin {
+    pub path: PathBuf,
+    pub year: u32,
+    pub day: u32,
+    parts: String,
+    solve: SolveFn,
+    free_answer: FreeAnswerFn,
+    // keeps the library mapped for as long as the function pointers are used
+    _library: Option<Library>,
+}
+
+impl Plugin {
+    fn from_descriptor(path: &Path, descriptor: &SolverDescriptor) -> Result<Plugin, PluginError> {
+        if descriptor.abi_version != PLUGIN_ABI_VERSION {
+            return Err(PluginError::IncompatibleVersion(
+                path.to_path_buf(),
+                descriptor.abi_version,
+            ));
+        }
+        let invalid = |reason: String| PluginError::InvalidDescriptor(path.to_path_buf(), reason);
+        if !DAYS.contains(&descriptor.day) {
+            return Err(invalid(format!("day {} is out of range", descriptor.day)));
+        }
+        if descriptor.parts.is_null() {
+            return Err(invalid("parts is null".to_string()));
+        }
+        let parts = unsafe { CStr::from_ptr(descriptor.parts) }
+            .to_str()
+            .map_err(|_| invalid("parts is not valid UTF-8".to_string()))?;
+        if parts.is_empty() || !parts.chars().all(|p| p == 'a' || p == 'b') {
+            return Err(invalid(format!(
+                "parts [{}] must be `a`, `b` or `ab`",
+                parts
+            )));
+        }
+        Ok(Plugin {
+            path: path.to_path_buf(),
+            year: descriptor.year,
+            day: descriptor.day,
+            parts: parts.to_string(),
+            solve: descriptor.solve,
+            free_answer: descriptor.free_answer,
+            _library: None,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Plugin, PluginError> {
+        // dlopen on Linux. Loading runs the library's initialisers, so only
+        // put trusted plugins in the plugins directory
+        let library = unsafe { Library::new(path) }
+            .map_err(|e| PluginError::Load(path.to_path_buf(), e.to_string()))?;
+        let descriptor_fn: Symbol<DescriptorFn> = unsafe { library.get(DESCRIPTOR_SYMBOL) }
+            .map_err(|_| PluginError::MissingDescriptor(path.to_path_buf()))?;
+        let descriptor = descriptor_fn();
+        if descriptor.is_null() {
+            return Err(PluginError::MissingDescriptor(path.to_path_buf()));
+        }
+        let mut plugin = Plugin::from_descriptor(path, unsafe { &*descriptor })?;
+        plugin._library = Some(library);
+        Ok(plugin)
+    }
+
+    pub fn has_part(&self, part: char) -> bool {
+        self.parts.contains(part)
+    }
+
+    pub fn solve(&self, part: char, input: &str) -> String {
+        let answer = (self.solve)(part as c_char, input.as_ptr(), input.len());
+        if answer.is_null() {
+            panic!(
+                "plugin {} failed to solve day [{}] part [{}]",
+                self.path.display(),
+                self.day,
+                part
+            );
+        }
+        let result = unsafe { CStr::from_ptr(answer) }
+            .to_string_lossy()
+            .into_owned();
+        (self.free_answer)(answer);
+        result
+    }
+}
+
+pub fn plugins_dir() -> PathBuf {
+    match env::var(PLUGINS_DIR_ENV_VAR) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => env::current_dir().unwrap().join(PLUGINS_DIR),
+    }
+}
+
+fn plugin_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.extension()
+                        .is_some_and(|e| e == env::consts::DLL_EXTENSION)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+pub fn load(dir: &Path) -> Vec<PluginError> {
+    // loads every plugin in `dir` once, returning the ones that were rejected.
+    // A missing plugins directory just means there are no plugins
+    let mut errors = Vec::new();
+    let (loaded, _) = PLUGINS.get_or_init(|| {
+        let plugins = plugin_paths(dir)
+            .iter()
+            .filter_map(|path| Plugin::load(path).map_err(|e| errors.push(e)).ok())
+            .collect();
+        (dir.to_path_buf(), plugins)
+    });
+    if loaded != dir {
+        errors.push(PluginError::AlreadyLoaded(
+            loaded.clone(),
+            dir.to_path_buf(),
+        ));
+    }
+    errors
+}
+
+pub fn loaded() -> &'static [Plugin] {
+    PLUGINS.get().map_or(&[], |(_, plugins)| plugins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    extern "C" fn solve_length(part: c_char, _input: *const u8, input_len: usize) -> *mut c_char {
+        CString::new(format!("{}{}", part as u8 as char, input_len))
+            .unwrap()
+            .into_raw()
+    }
+
+    extern "C" fn free_answer(answer: *mut c_char) {
+        drop(unsafe { CString::from_raw(answer) });
+    }
+
+    fn descriptor(abi_version: u32, day: u32, parts: &CStr) -> SolverDescriptor {
+        SolverDescriptor {
+            abi_version,
+            year: 2022,
+            day,
+            parts: parts.as_ptr(),
+            solve: solve_length,
+            free_answer,
+        }
+    }
+
+    #[test]
+    fn test_from_descriptor() {
+        let d = descriptor(PLUGIN_ABI_VERSION, 13, c"a");
+        let plugin = Plugin::from_descriptor(Path::new("day_13.so"), &d).unwrap();
+        assert_eq!((plugin.year, plugin.day), (2022, 13));
+        assert!(plugin.has_part('a'));
+        assert!(!plugin.has_part('b'));
+        assert_eq!(plugin.solve('a', "hello"), "a5");
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let d = descriptor(PLUGIN_ABI_VERSION + 1, 13, c"ab");
+        assert_eq!(
+            Plugin::from_descriptor(Path::new("day_13.so"), &d).err(),
+            Some(PluginError::IncompatibleVersion(
+                PathBuf::from("day_13.so"),
+                PLUGIN_ABI_VERSION + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_descriptors() {
+        let path = Path::new("day_13.so");
+        assert!(Plugin::from_descriptor(path, &descriptor(PLUGIN_ABI_VERSION, 26, c"ab")).is_err());
+        assert!(Plugin::from_descriptor(path, &descriptor(PLUGIN_ABI_VERSION, 13, c"c")).is_err());
+        assert!(Plugin::from_descriptor(path, &descriptor(PLUGIN_ABI_VERSION, 13, c"")).is_err());
+    }
+
+    #[test]
+    fn test_loads_one_directory() {
+        // missing directories have no plugins, but only the first is loaded
+        let (first, second) = (Path::new("no_plugins_here"), Path::new("nor_here"));
+        assert_eq!(load(first), vec![]);
+        assert_eq!(load(first), vec![]);
+        assert_eq!(
+            load(second),
+            vec![PluginError::AlreadyLoaded(
+                first.to_path_buf(),
+                second.to_path_buf()
+            )]
+        );
+        assert!(loaded().is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::grid::Grid;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// each cell of a grid or point set becomes one pixel, scaled up by this much
+// so small puzzle states are still visible
+pub const DEFAULT_SCALE: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+pub const BLACK: Rgb = Rgb(0, 0, 0);
+pub const WHITE: Rgb = Rgb(255, 255, 255);
+pub const GREEN: Rgb = Rgb(0, 200, 0);
+pub const RED: Rgb = Rgb(220, 0, 0);
+pub const GOLD: Rgb = Rgb(255, 204, 0);
+pub const BLUE: Rgb = Rgb(30, 90, 255);
+
+impl Rgb {
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+pub fn gradient(value: u32, max: u32, from: Rgb, to: Rgb) -> Rgb {
+    // linear interpolation between two colours for value in 0..=max
+    let t = if max == 0 {
+        0.0
+    } else {
+        value.min(max) as f64 / max as f64
+    };
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    Rgb(lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        match path.extension()?.to_str()? {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png),
+            "svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn from_grid<T>(grid: &Grid<T>, colour: impl Fn(&T) -> Rgb) -> Image {
+        let mut image = Image::new(grid.width(), grid.height(), BLACK);
+        for ((x, y), cell) in grid.iter() {
+            image.set(x, y, colour(cell));
+        }
+        image
+    }
+
+    pub fn from_points(
+        points: impl IntoIterator<Item = (i64, i64)>,
+        colour: Rgb,
+        background: Rgb,
+    ) -> Image {
+        // the image covers the bounding box of the points. Points use maths
+        // coordinates, so y is flipped to put positive y at the top
+        let points: Vec<(i64, i64)> = points.into_iter().collect();
+        let min_x = points.iter().map(|p| p.0).min().unwrap_or(0);
+        let max_x = points.iter().map(|p| p.0).max().unwrap_or(0);
+        let min_y = points.iter().map(|p| p.1).min().unwrap_or(0);
+        let max_y = points.iter().map(|p| p.1).max().unwrap_or(0);
+
+        let width = (max_x - min_x + 1) as usize;
+        let height = (max_y - min_y + 1) as usize;
+        let mut image = Image::new(width, height, background);
+        for (x, y) in points {
+            image.set((x - min_x) as usize, (max_y - y) as usize, colour);
+        }
+        image
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        self.pixels[y * self.width + x] = colour;
+    }
+
+    pub fn scaled(&self, scale: usize) -> Image {
+        let mut image = Image::new(self.width * scale, self.height * scale, BLACK);
+        for y in 0..image.height {
+            for x in 0..image.width {
+                image.set(x, y, self.get(x / scale, y / scale));
+            }
+        }
+        image
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let header = format!("P6\n{} {}\n255\n", self.width, self.height);
+        let body = self.pixels.iter().flat_map(|p| [p.0, p.1, p.2]);
+        header.bytes().chain(body).collect()
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut data, self.width as u32, self.height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            let body: Vec<u8> = self.pixels.iter().flat_map(|p| [p.0, p.1, p.2]).collect();
+            writer.write_image_data(&body).unwrap();
+        }
+        data
+    }
+
+    pub fn to_svg(&self, scale: usize) -> String {
+        // runs of the same colour in a row are merged into a single rect to
+        // keep the file size down
+        let mut rects: Vec<String> = Vec::new();
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                let colour = self.get(x, y);
+                let run = (x..self.width)
+                    .take_while(|i| self.get(*i, y) == colour)
+                    .count();
+                rects.push(format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    x * scale,
+                    y * scale,
+                    run * scale,
+                    scale,
+                    colour.hex()
+                ));
+                x += run;
+            }
+        }
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" shape-rendering="crispEdges">{}</svg>"#,
+            self.width * scale,
+            self.height * scale,
+            rects.join("")
+        ) + "\n"
+    }
+
+    pub fn write(&self, path: &Path, scale: usize) {
+        let format = ImageFormat::from_path(path).unwrap_or_else(|| {
+            panic!(
+                "cannot render to {}, use a .ppm, .png or .svg file",
+                path.display()
+            )
+        });
+        let data = match format {
+            ImageFormat::Ppm => self.scaled(scale).to_ppm(),
+            ImageFormat::Png => self.scaled(scale).to_png(),
+            ImageFormat::Svg => self.to_svg(scale).into_bytes(),
+        };
+        let file =
+            File::create(path).unwrap_or_else(|_| panic!("could not create {}", path.display()));
+        BufWriter::new(file).write_all(&data).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_points() {
+        let image = Image::from_points(vec![(0, 0), (2, 1)], WHITE, BLACK);
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(image.get(0, 1), WHITE);
+        assert_eq!(image.get(2, 0), WHITE);
+        assert_eq!(image.get(0, 0), BLACK);
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        let grid = Grid::from_rows(vec![vec![true, false]]).unwrap();
+        let image = Image::from_grid(&grid, |c| if *c { WHITE } else { BLACK });
+        assert_eq!(image.to_ppm(), b"P6\n2 1\n255\n\xff\xff\xff\x00\x00\x00");
+    }
+
+    #[test]
+    fn test_to_svg_merges_runs() {
+        let image = Image::new(3, 1, RED);
+        assert_eq!(image.to_svg(1).matches("<rect").count(), 1);
+    }
+
+    #[test]
+    fn test_gradient() {
+        assert_eq!(gradient(0, 10, BLACK, WHITE), BLACK);
+        assert_eq!(gradient(10, 10, BLACK, WHITE), WHITE);
+        assert_eq!(gradient(5, 10, BLACK, Rgb(200, 100, 0)), Rgb(100, 50, 0));
+    }
+}
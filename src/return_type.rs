@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// answers keep their type so checks compare like with like, and serialise
+// as `{"type": "u64", "value": 10605}` for JSON output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum ReturnType {
+    #[serde(rename = "u32")]
+    UInt32(u32),
+    #[serde(rename = "u64")]
+    UInt64(u64),
+    #[serde(rename = "u128")]
+    UInt128(u128),
+    #[serde(rename = "usize")]
+    USize(usize),
+    #[serde(rename = "i64")]
+    Int64(i64),
+    #[serde(rename = "i128")]
+    Int128(i128),
+    #[serde(rename = "string")]
+    String(String),
+    // e.g. day 10's CRT, serialised as a list of rows
+    #[serde(rename = "grid", with = "grid_rows")]
+    Grid(Vec<Vec<char>>),
+}
+
+macro_rules! from_type {
+    ($type:ty, $variant:ident) => {
+        impl From<$type> for ReturnType {
+            fn from(value: $type) -> Self {
+                Self::$variant(value)
+            }
+        }
+    };
+}
+
+from_type!(u32, UInt32);
+from_type!(u64, UInt64);
+from_type!(u128, UInt128);
+from_type!(usize, USize);
+from_type!(i64, Int64);
+from_type!(i128, Int128);
+from_type!(String, String);
+from_type!(Vec<Vec<char>>, Grid);
+
+impl ReturnType {
+    pub fn parse_as(&self, answer: &str) -> Option<ReturnType> {
+        // reads a stored answer as the same type as this one, so `007` matches
+        // 7 and grids saved with a leading newline still match
+        let number = answer.trim();
+        match self {
+            ReturnType::UInt32(_) => number.parse().ok().map(ReturnType::UInt32),
+            ReturnType::UInt64(_) => number.parse().ok().map(ReturnType::UInt64),
+            ReturnType::UInt128(_) => number.parse().ok().map(ReturnType::UInt128),
+            ReturnType::USize(_) => number.parse().ok().map(ReturnType::USize),
+            ReturnType::Int64(_) => number.parse().ok().map(ReturnType::Int64),
+            ReturnType::Int128(_) => number.parse().ok().map(ReturnType::Int128),
+            ReturnType::String(_) => Some(ReturnType::String(answer.to_string())),
+            ReturnType::Grid(_) => Some(ReturnType::Grid(
+                answer
+                    .trim_matches('\n')
+                    .lines()
+                    .map(|row| row.chars().collect())
+                    .collect(),
+            )),
+        }
+    }
+
+    pub fn matches(&self, answer: &str) -> bool {
+        self.parse_as(answer).as_ref() == Some(self)
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, ReturnType::Grid(rows) if rows.len() > 1)
+    }
+}
+
+impl fmt::Display for ReturnType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReturnType::UInt32(value) => write!(f, "{}", value),
+            ReturnType::UInt64(value) => write!(f, "{}", value),
+            ReturnType::UInt128(value) => write!(f, "{}", value),
+            ReturnType::USize(value) => write!(f, "{}", value),
+            ReturnType::Int64(value) => write!(f, "{}", value),
+            ReturnType::Int128(value) => write!(f, "{}", value),
+            ReturnType::String(value) => write!(f, "{}", value),
+            ReturnType::Grid(rows) => {
+                let rows: Vec<String> = rows.iter().map(|row| row.iter().collect()).collect();
+                write!(f, "{}", rows.join("\n"))
+            }
+        }
+    }
+}
+
+mod grid_rows {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(rows: &[Vec<char>], s: S) -> Result<S::Ok, S::Error> {
+        let rows: Vec<String> = rows.iter().map(|row| row.iter().collect()).collect();
+        rows.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Vec<char>>, D::Error> {
+        let rows = Vec::<String>::deserialize(d)?;
+        Ok(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        assert!(ReturnType::UInt64(7).matches("007"));
+        assert!(!ReturnType::UInt64(7).matches("8"));
+        assert!(!ReturnType::UInt64(7).matches("seven"));
+
+        let grid = ReturnType::Grid(vec![vec!['#', '.'], vec!['.', '#']]);
+        assert!(grid.matches("#.\n.#"));
+        assert!(grid.matches("\n#.\n.#"));
+    }
+
+    #[test]
+    fn test_json() {
+        let grid = ReturnType::Grid(vec![vec!['#', '.'], vec!['.', '#']]);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(json, r###"{"type":"grid","value":["#.",".#"]}"###);
+        assert_eq!(serde_json::from_str::<ReturnType>(&json).unwrap(), grid);
+
+        let big = ReturnType::UInt128(u64::MAX as u128 + 1);
+        assert_eq!(
+            serde_json::to_string(&big).unwrap(),
+            r#"{"type":"u128","value":18446744073709551616}"#
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = ReturnType::Grid(vec![vec!['#', '.'], vec!['.', '#']]);
+        assert_eq!(grid.to_string(), "#.\n.#");
+        assert_eq!(ReturnType::Int128(-5).to_string(), "-5");
+    }
+}
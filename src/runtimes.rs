@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// the latest runtime of each part against the real inputs. This is machine
+// specific so it is kept out of the repo. One runtime per line:
+// `<year> <day> <part> <microseconds>`
+pub const RUNTIMES_FILE: &str = ".aoc_runtimes";
+
+pub struct Runtimes {
+    path: PathBuf,
+    entries: BTreeMap<(u32, u32, char), Duration>,
+}
+
+fn parse_line(line: &str) -> Option<((u32, u32, char), Duration)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let year = fields.first()?.parse::<u32>().ok()?;
+    let day = fields.get(1)?.parse::<u32>().ok()?;
+    let part = fields.get(2)?.chars().next()?;
+    let micros = fields.get(3)?.parse::<u64>().ok()?;
+    Some(((year, day, part), Duration::from_micros(micros)))
+}
+
+impl Runtimes {
+    pub fn load(dir: &Path) -> Runtimes {
+        let path = dir.join(RUNTIMES_FILE);
+        let entries = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(parse_line)
+            .collect();
+        Runtimes { path, entries }
+    }
+
+    pub fn get(&self, year: u32, day: u32, part: char) -> Option<&Duration> {
+        self.entries.get(&(year, day, part))
+    }
+
+    pub fn record(&mut self, year: u32, day: u32, part: char, runtime: Duration) {
+        self.entries.insert((year, day, part), runtime);
+    }
+
+    pub fn save(&self) {
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|((year, day, part), runtime)| {
+                format!("{} {} {} {}\n", year, day, part, runtime.as_micros())
+            })
+            .collect();
+        fs::write(&self.path, contents)
+            .unwrap_or_else(|_| panic!("could not write {}", self.path.display()));
+    }
+}
+
+pub fn format_runtime(runtime: &Duration) -> String {
+    if runtime.as_secs() > 0 {
+        format!("{:.2}s", runtime.as_secs_f64())
+    } else {
+        format!("{:.2}ms", runtime.as_secs_f64() * 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("2022 12 b 1500"),
+            Some(((2022, 12, 'b'), Duration::from_micros(1500)))
+        );
+        assert_eq!(parse_line("2022 12 b"), None);
+    }
+
+    #[test]
+    fn test_format_runtime() {
+        assert_eq!(format_runtime(&Duration::from_micros(1500)), "1.50ms");
+        assert_eq!(format_runtime(&Duration::from_millis(5250)), "5.25s");
+    }
+}
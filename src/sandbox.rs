@@ -0,0 +1,283 @@
+use std::fmt;
+use std::process::{Command, Output};
+use std::time::Duration;
+
+// runs a solver in a child process under `setrlimit` caps, so a runaway
+// simulation is stopped by the kernel instead of taking the machine with it.
+// The child is this binary again, solving a single part with `--json`
+
+pub const DEFAULT_MEMORY_MB: u64 = 2048;
+pub const DEFAULT_CPU_SECONDS: u64 = 60;
+
+// std's default allocation error handler prints this before aborting. It's
+// the only sign an abort was an allocation failure, so a custom handler or a
+// change to the wording would make out of memory runs show up as crashes
+const ALLOCATION_FAILED: &str = "memory allocation of";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub memory_mb: u64,
+    pub cpu_seconds: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SandboxError {
+    OutOfMemory { limit_mb: u64 },
+    CpuLimit { limit_seconds: u64 },
+    Crashed(String),
+}
+
+impl fmt::Display for SandboxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SandboxError::OutOfMemory { limit_mb } => {
+                write!(f, "out of memory, limit {} MB", limit_mb)
+            }
+            SandboxError::CpuLimit { limit_seconds } => {
+                write!(f, "CPU limit reached, limit {}s", limit_seconds)
+            }
+            SandboxError::Crashed(message) => write!(f, "crashed: {}", message),
+        }
+    }
+}
+
+impl SandboxError {
+    // a short name for JSON output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SandboxError::OutOfMemory { .. } => "oom",
+            SandboxError::CpuLimit { .. } => "cpu-limit",
+            SandboxError::Crashed(_) => "crashed",
+        }
+    }
+}
+
+fn panic_message(stderr: &str) -> String {
+    // a panic prints `thread 'main' panicked at <location>:` then the message
+    let mut lines = stderr.lines().skip_while(|l| !l.contains("panicked at"));
+    let location = lines.next();
+    match (location, lines.next()) {
+        (Some(_), Some(message)) => message.to_string(),
+        _ => stderr
+            .lines()
+            .rfind(|l| !l.trim().is_empty())
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+fn classify(output: &Output, cpu_time: Duration, limits: &Limits) -> Result<String, SandboxError> {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // the runner exits with 1 once it has reported a part that failed, e.g.
+    // on an overflow or unreadable letters, so that's a finished run too
+    let reported = output.status.code() == Some(1) && !output.stdout.is_empty();
+    if output.status.success() || reported {
+        // pass on anything else the child had to say, e.g. its trace
+        eprint!("{}", stderr);
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    if aborted(output) && stderr.contains(ALLOCATION_FAILED) {
+        return Err(SandboxError::OutOfMemory {
+            limit_mb: limits.memory_mb,
+        });
+    }
+    if killed_for_cpu(output, cpu_time, limits) {
+        return Err(SandboxError::CpuLimit {
+            limit_seconds: limits.cpu_seconds,
+        });
+    }
+    if stderr.trim().is_empty() {
+        // e.g. the limits are too tight for the binary to even load
+        return Err(SandboxError::Crashed(format!(
+            "no output, {}",
+            output.status
+        )));
+    }
+    Err(SandboxError::Crashed(panic_message(&stderr)))
+}
+
+#[cfg(unix)]
+fn aborted(output: &Output) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    output.status.signal() == Some(libc::SIGABRT)
+}
+
+#[cfg(unix)]
+fn killed_for_cpu(output: &Output, cpu_time: Duration, limits: &Limits) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    // SIGXCPU at the soft limit, SIGKILL if the child ignored it until the
+    // hard one. Anything can send SIGKILL, so that only counts once the
+    // child has used up its CPU time
+    match output.status.signal() {
+        Some(libc::SIGXCPU) => true,
+        Some(libc::SIGKILL) => cpu_time >= Duration::from_secs(limits.cpu_seconds),
+        _ => false,
+    }
+}
+
+#[cfg(unix)]
+fn output_and_cpu_time(mut command: Command) -> std::io::Result<(Output, Duration)> {
+    // like `Command::output`, but reaps the child with wait4 to get its CPU time
+    use std::io::Read;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Stdio;
+    use std::thread;
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // read both pipes at once, so a child filling one doesn't block on it
+    let mut stderr_pipe = child.stderr.take().unwrap();
+    let stderr_reader = thread::spawn(move || {
+        let mut stderr = Vec::new();
+        stderr_pipe.read_to_end(&mut stderr).map(|_| stderr)
+    });
+    let mut stdout = Vec::new();
+    child.stdout.take().unwrap().read_to_end(&mut stdout)?;
+    let stderr = stderr_reader.join().unwrap()?;
+
+    let mut status = 0;
+    // SAFETY: rusage is plain data that wait4 fills in
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: the child hasn't been waited on, so its pid is still ours
+    if unsafe { libc::wait4(child.id() as libc::pid_t, &mut status, 0, &mut usage) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let time = |t: libc::timeval| Duration::new(t.tv_sec as u64, t.tv_usec as u32 * 1000);
+    let output = Output {
+        status: std::process::ExitStatus::from_raw(status),
+        stdout,
+        stderr,
+    };
+    Ok((output, time(usage.ru_utime) + time(usage.ru_stime)))
+}
+
+#[cfg(unix)]
+fn limit(mut command: Command, limits: Limits) -> Command {
+    use std::os::unix::process::CommandExt;
+    let set = |resource, soft: u64, hard: u64| {
+        let limit = libc::rlimit {
+            rlim_cur: soft as libc::rlim_t,
+            rlim_max: hard as libc::rlim_t,
+        };
+        // SAFETY: setrlimit only reads the struct passed to it
+        if unsafe { libc::setrlimit(resource, &limit) } == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    };
+    let bytes = limits.memory_mb * 1024 * 1024;
+    // SAFETY: the closure runs between fork and exec, where only
+    // async-signal-safe calls are allowed. setrlimit is one
+    unsafe {
+        command.pre_exec(move || {
+            set(libc::RLIMIT_AS, bytes, bytes)?;
+            set(libc::RLIMIT_CPU, limits.cpu_seconds, limits.cpu_seconds + 1)
+        });
+    }
+    command
+}
+
+#[cfg(not(unix))]
+fn aborted(_output: &Output) -> bool {
+    false
+}
+
+#[cfg(not(unix))]
+fn killed_for_cpu(_output: &Output, _cpu_time: Duration, _limits: &Limits) -> bool {
+    false
+}
+
+#[cfg(not(unix))]
+fn output_and_cpu_time(_command: Command) -> std::io::Result<(Output, Duration)> {
+    unreachable!("`limit` refuses to sandbox off unix")
+}
+
+#[cfg(not(unix))]
+fn limit(_command: Command, _limits: Limits) -> Command {
+    panic!("--sandbox relies on setrlimit, which is only available on unix");
+}
+
+pub fn run(command: Command, limits: Limits) -> Result<String, SandboxError> {
+    // returns the child's stdout when it exits cleanly
+    let (output, cpu_time) = output_and_cpu_time(limit(command, limits))
+        .unwrap_or_else(|e| panic!("could not start the sandboxed solver: {}", e));
+    classify(&output, cpu_time, &limits)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    const LIMITS: Limits = Limits {
+        memory_mb: 64,
+        cpu_seconds: 1,
+    };
+
+    fn sh(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+
+    #[test]
+    fn test_finished() {
+        assert_eq!(run(sh("echo 42"), LIMITS), Ok("42\n".to_string()));
+        assert_eq!(
+            run(sh("echo '{\"error\":\"overflow\"}'; exit 1"), LIMITS),
+            Ok("{\"error\":\"overflow\"}\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_limits_are_typed() {
+        assert_eq!(
+            run(sh("while :; do :; done"), LIMITS),
+            Err(SandboxError::CpuLimit { limit_seconds: 1 })
+        );
+        // ignoring SIGXCPU only lasts until the hard limit's SIGKILL
+        assert_eq!(
+            run(sh("trap '' XCPU; while :; do :; done"), LIMITS),
+            Err(SandboxError::CpuLimit { limit_seconds: 1 })
+        );
+        assert_eq!(
+            run(
+                sh("echo 'memory allocation of 4096 bytes failed' >&2; kill -ABRT $$"),
+                LIMITS
+            ),
+            Err(SandboxError::OutOfMemory { limit_mb: 64 })
+        );
+    }
+
+    #[test]
+    fn test_other_kills_are_crashes() {
+        // a SIGKILL that isn't from the CPU limit, e.g. the OOM killer
+        assert!(matches!(
+            run(sh("kill -KILL $$"), LIMITS),
+            Err(SandboxError::Crashed(_))
+        ));
+        // the allocator's message without the abort that follows it
+        assert!(matches!(
+            run(
+                sh("echo 'memory allocation of 4096 bytes failed' >&2; exit 3"),
+                LIMITS
+            ),
+            Err(SandboxError::Crashed(_))
+        ));
+    }
+
+    #[test]
+    fn test_crash_reports_panic_message() {
+        let stderr = "\nthread 'main' panicked at src/days/day_9.rs:40:9:\nHead and Tail are separated by an invalid distance\nnote: run with `RUST_BACKTRACE=1`";
+        assert_eq!(
+            panic_message(stderr),
+            "Head and Tail are separated by an invalid distance"
+        );
+        assert_eq!(
+            run(sh("echo oops >&2; exit 3"), LIMITS),
+            Err(SandboxError::Crashed("oops".to_string()))
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+// searches over implicit graphs: nodes are any hashable value and edges come
+// from a `neighbours` closure, so nothing is built up front. Each search stops
+// as soon as a node matches `is_goal`, pass `|_| false` to explore everything
+// reachable instead
+
+pub type Cost = u64;
+
+// the outcome of a search: the cost of every node it settled, how it got
+// there and the goal it stopped at, if any
+pub struct Search<N> {
+    costs: HashMap<N, Cost>,
+    parents: HashMap<N, N>,
+    goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new() -> Search<N> {
+        Search {
+            costs: HashMap::new(),
+            parents: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    fn settle(&mut self, node: N, cost: Cost, parent: Option<N>) {
+        if let Some(parent) = parent {
+            self.parents.insert(node.clone(), parent);
+        }
+        self.costs.insert(node, cost);
+    }
+
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    pub fn goal_cost(&self) -> Option<Cost> {
+        self.cost(self.goal.as_ref()?)
+    }
+
+    pub fn cost(&self, node: &N) -> Option<Cost> {
+        self.costs.get(node).copied()
+    }
+
+    pub fn visited(&self) -> impl Iterator<Item = (&N, Cost)> {
+        self.costs.iter().map(|(node, cost)| (node, *cost))
+    }
+
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        // from the start the search reached `node` from, to `node`
+        if !self.costs.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+pub fn bfs<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    multi_bfs([start], neighbours, is_goal)
+}
+
+pub fn multi_bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    // every edge costs 1, and the cost of a node is its distance to the
+    // nearest start
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if search.costs.contains_key(&start) {
+            continue;
+        }
+        search.settle(start.clone(), 0, None);
+        if is_goal(&start) {
+            search.goal = Some(start);
+            return search;
+        }
+        queue.push_back(start);
+    }
+    while let Some(node) = queue.pop_front() {
+        let cost = search.costs[&node] + 1;
+        for next in neighbours(&node) {
+            if search.costs.contains_key(&next) {
+                continue;
+            }
+            search.settle(next.clone(), cost, Some(node.clone()));
+            if is_goal(&next) {
+                search.goal = Some(next);
+                return search;
+            }
+            queue.push_back(next);
+        }
+    }
+    search
+}
+
+pub fn dijkstra<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+pub fn astar<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> Cost,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    // nodes are settled as they leave the queue, so the heuristic must be
+    // consistent: it may never drop by more than the cost of the edge taken
+    let mut search = Search::new();
+    // the best cost found so far for nodes waiting in the queue
+    let mut tentative: HashMap<N, (Cost, Option<N>)> = HashMap::new();
+    // the queue holds indices into `entries` so nodes needn't be `Ord`
+    let mut entries = vec![start.clone()];
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    tentative.insert(start, (0, None));
+
+    while let Some(Reverse((_, cost, entry))) = queue.pop() {
+        let node = entries[entry].clone();
+        match tentative.get(&node) {
+            // already settled, or a better route was queued after this one
+            None => continue,
+            Some((best, _)) if *best < cost => continue,
+            _ => (),
+        }
+        let (_, parent) = tentative.remove(&node).unwrap();
+        search.settle(node.clone(), cost, parent);
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        for (next, step) in neighbours(&node) {
+            if search.costs.contains_key(&next) {
+                continue;
+            }
+            let next_cost = cost + step;
+            if tentative
+                .get(&next)
+                .is_none_or(|(best, _)| next_cost < *best)
+            {
+                queue.push(Reverse((
+                    next_cost + heuristic(&next),
+                    next_cost,
+                    entries.len(),
+                )));
+                entries.push(next.clone());
+                tentative.insert(next, (next_cost, Some(node.clone())));
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 5x5 grid with a wall down x = 2, open only at y = 4
+    fn open(x: i32, y: i32) -> bool {
+        (0..5).contains(&x) && (0..5).contains(&y) && (x != 2 || y == 4)
+    }
+
+    fn neighbours(&(x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+            .into_iter()
+            .filter(|&(x, y)| open(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let search = bfs((0, 0), neighbours, |&n| n == (4, 0));
+        assert_eq!(search.goal_cost(), Some(12));
+        let path = search.path().unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!((path[0], path[12]), ((0, 0), (4, 0)));
+        assert!(path.contains(&(2, 4)));
+
+        // unreachable goals explore everything and find nothing
+        let search = bfs((0, 0), neighbours, |&n| n == (2, 0));
+        assert_eq!((search.goal(), search.visited().count()), (None, 21));
+        assert_eq!(search.cost(&(4, 4)), Some(8));
+    }
+
+    #[test]
+    fn test_multi_bfs() {
+        let search = multi_bfs([(0, 0), (4, 4)], neighbours, |&n| n == (3, 0));
+        assert_eq!(search.goal_cost(), Some(5));
+        assert_eq!(search.path().unwrap()[0], (4, 4));
+        assert_eq!(search.path_to(&(0, 1)), Some(vec![(0, 0), (0, 1)]));
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        // steps to the right cost 1 and any other step costs 3
+        let weighted = |&(x, y): &(i32, i32)| {
+            neighbours(&(x, y))
+                .into_iter()
+                .map(move |n| (n, if n.0 > x { 1 } else { 3 }))
+        };
+        let goal = |n: &(i32, i32)| *n == (4, 0);
+        let cheapest = dijkstra((0, 0), weighted, goal);
+        assert_eq!(cheapest.goal_cost(), Some(28));
+
+        let manhattan = |&(x, y): &(i32, i32)| (4 - x).unsigned_abs() as Cost + y as Cost;
+        let guided = astar((0, 0), weighted, manhattan, goal);
+        assert_eq!(guided.goal_cost(), Some(28));
+        assert_eq!(guided.path(), cheapest.path());
+        assert!(guided.visited().count() <= cheapest.visited().count());
+    }
+}
@@ -0,0 +1,203 @@
+use crate::animation::{self, Frame};
+use std::collections::BTreeMap;
+
+// puzzles that apply a rule over and over (moving crates, knots, clock cycles,
+// monkey rounds) implement `Simulation`, and `Driver` runs them. The driver
+// counts steps, pushes a frame after each step when animating, and keeps
+// periodic snapshots so a run can be rewound to any earlier step
+pub trait Simulation {
+    // what callers and predicates look at between steps
+    type State;
+    // enough to put the simulation back exactly as it was
+    type Snapshot;
+
+    // advances one step, false once there's nothing left to do
+    fn step(&mut self) -> bool;
+    fn state(&self) -> &Self::State;
+    fn snapshot(&self) -> Self::Snapshot;
+    fn restore(&mut self, snapshot: &Self::Snapshot);
+
+    fn frame(&self) -> Option<Frame> {
+        // simulations that can be animated draw their current state here
+        None
+    }
+}
+
+pub struct Driver<S: Simulation> {
+    simulation: S,
+    steps: usize,
+    snapshot_every: Option<usize>,
+    snapshots: BTreeMap<usize, S::Snapshot>,
+}
+
+impl<S: Simulation> Driver<S> {
+    pub fn new(simulation: S) -> Driver<S> {
+        Driver {
+            simulation,
+            steps: 0,
+            snapshot_every: None,
+            snapshots: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_snapshots(mut self, every: usize) -> Driver<S> {
+        // snapshots the current step and then every `every` steps
+        self.snapshot_every = Some(every.max(1));
+        self.snapshots
+            .insert(self.steps, self.simulation.snapshot());
+        self
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn state(&self) -> &S::State {
+        self.simulation.state()
+    }
+
+    pub fn simulation(&self) -> &S {
+        &self.simulation
+    }
+
+    pub fn into_simulation(self) -> S {
+        self.simulation
+    }
+
+    pub fn step(&mut self) -> bool {
+        if !self.simulation.step() {
+            return false;
+        }
+        self.steps += 1;
+        if self
+            .snapshot_every
+            .is_some_and(|every| self.steps.is_multiple_of(every))
+        {
+            self.snapshots
+                .insert(self.steps, self.simulation.snapshot());
+        }
+        if animation::enabled() {
+            if let Some(frame) = self.simulation.frame() {
+                animation::push(frame);
+            }
+        }
+        true
+    }
+
+    pub fn run(&mut self) -> usize {
+        // runs until the simulation finishes, returning the step it ended on
+        while self.step() {}
+        self.steps
+    }
+
+    pub fn run_to(&mut self, step: usize) -> bool {
+        // false if the simulation finished before reaching `step`
+        while self.steps < step {
+            if !self.step() {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn run_until(&mut self, mut predicate: impl FnMut(&S::State) -> bool) -> bool {
+        // stops at the first state, including the current one, that matches.
+        // False if the simulation finished without one matching
+        while !predicate(self.simulation.state()) {
+            if !self.step() {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn rewind_to(&mut self, step: usize) {
+        // restores the closest snapshot at or before `step` and replays from it
+        let (from, snapshot) = self
+            .snapshots
+            .range(..=step)
+            .next_back()
+            .unwrap_or_else(|| {
+                panic!(
+                    "no snapshot at or before step {}, run with_snapshots to rewind",
+                    step
+                )
+            });
+        self.simulation.restore(snapshot);
+        self.steps = *from;
+        self.run_to(step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // doubles a number until it reaches a limit
+    struct Doubling {
+        value: u64,
+        limit: u64,
+    }
+
+    impl Simulation for Doubling {
+        type State = u64;
+        type Snapshot = u64;
+
+        fn step(&mut self) -> bool {
+            if self.value >= self.limit {
+                return false;
+            }
+            self.value *= 2;
+            true
+        }
+
+        fn state(&self) -> &u64 {
+            &self.value
+        }
+
+        fn snapshot(&self) -> u64 {
+            self.value
+        }
+
+        fn restore(&mut self, snapshot: &u64) {
+            self.value = *snapshot;
+        }
+    }
+
+    fn driver() -> Driver<Doubling> {
+        Driver::new(Doubling {
+            value: 1,
+            limit: 1000,
+        })
+    }
+
+    #[test]
+    fn test_run() {
+        let mut driver = driver();
+        assert!(driver.run_to(3));
+        assert_eq!(*driver.state(), 8);
+        assert!(driver.run_until(|v| *v > 100));
+        assert_eq!((driver.steps(), *driver.state()), (7, 128));
+        assert_eq!(driver.run(), 10);
+        assert!(!driver.run_to(20));
+        assert!(!driver.run_until(|v| *v == 3));
+    }
+
+    #[test]
+    fn test_rewind() {
+        let mut driver = driver().with_snapshots(4);
+        driver.run();
+        driver.rewind_to(6);
+        assert_eq!((driver.steps(), *driver.state()), (6, 64));
+        driver.rewind_to(0);
+        assert_eq!(*driver.state(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "no snapshot at or before step 2")]
+    fn test_rewind_without_snapshots() {
+        let mut driver = driver();
+        driver.run();
+        driver.rewind_to(2);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::parser::{parse_all, Parser};
+use std::io::BufRead;
+
+// streaming solvers read their input a line at a time into one reused buffer,
+// so memory stays flat however large the input is. Bad input panics with the
+// same messages as `parser::expect_parse`, positioned in the whole input
+
+pub fn for_each_line(mut reader: impl BufRead, mut f: impl FnMut(usize, &str)) {
+    // `f` is given each line's 0-based index and its text without the newline
+    let mut buffer = String::new();
+    let mut idx = 0;
+    loop {
+        buffer.clear();
+        let read = reader
+            .read_line(&mut buffer)
+            .unwrap_or_else(|e| panic!("could not read input: {}", e));
+        if read == 0 {
+            break;
+        }
+        let line = buffer.strip_suffix('\n').unwrap_or(&buffer);
+        f(idx, line.strip_suffix('\r').unwrap_or(line));
+        idx += 1;
+    }
+}
+
+pub fn parse_line<'a, T>(parser: impl Parser<'a, T>, idx: usize, line: &'a str) -> T {
+    parse_all(parser, line).unwrap_or_else(|mut e| {
+        e.line += idx;
+        panic!("invalid input at {}", e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::integer;
+
+    #[test]
+    fn test_for_each_line() {
+        let mut lines = Vec::new();
+        for_each_line("a\r\nb\n\nc".as_bytes(), |idx, line| {
+            lines.push((idx, line.to_string()))
+        });
+        assert_eq!(
+            lines,
+            vec![
+                (0, "a".to_string()),
+                (1, "b".to_string()),
+                (2, String::new()),
+                (3, "c".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid input at line 4, column 1: expected a number")]
+    fn test_parse_line_error_position() {
+        parse_line(integer::<u32>(), 3, "x");
+    }
+}
@@ -0,0 +1,186 @@
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Mutex;
+
+// solvers narrate their intermediate steps with the `explain!` macro. Events
+// are only built when the explain level asks for them, so when explaining is
+// off (the default) the cost is a single atomic load per call site
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+static JSON: AtomicBool = AtomicBool::new(false);
+// while capturing, events are kept here instead of going to stderr, e.g. for
+// the dashboard, which owns the terminal. Past the limit they're only counted
+static CAPTURED: Mutex<Option<Captured>> = Mutex::new(None);
+const CAPTURE_LIMIT: usize = 10_000;
+
+struct Captured {
+    lines: Vec<String>,
+    dropped: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventKind {
+    Step,
+    Decision,
+    State,
+}
+
+impl EventKind {
+    fn level(&self) -> u8 {
+        // level 1 narrates what happens, level 2 adds full state snapshots
+        match self {
+            EventKind::Step | EventKind::Decision => 1,
+            EventKind::State => 2,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            EventKind::Step => "step",
+            EventKind::Decision => "decision",
+            EventKind::State => "state",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Event<'a> {
+    source: &'a str,
+    kind: &'a str,
+    message: &'a str,
+    #[serde(serialize_with = "serialize_fields")]
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+fn serialize_fields<S: Serializer>(fields: &[(&str, &str)], s: S) -> Result<S::Ok, S::Error> {
+    // fields are rendered as a JSON object, keeping the order they were given in
+    let mut map = s.serialize_map(Some(fields.len()))?;
+    for (k, v) in fields {
+        map.serialize_entry(k, v)?;
+    }
+    map.end()
+}
+
+pub fn set_explain(level: u8, json: bool) {
+    LEVEL.store(level, Ordering::Relaxed);
+    JSON.store(json, Ordering::Relaxed);
+}
+
+#[inline]
+pub fn enabled(kind: EventKind) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= kind.level()
+}
+
+pub fn source_name(module_path: &str) -> &str {
+    // `advent_of_code_2022::days::day_9` -> `day_9`
+    module_path.rsplit("::").next().unwrap()
+}
+
+fn render(event: &Event, json: bool) -> String {
+    if json {
+        return serde_json::to_string(event).unwrap();
+    }
+    let fields: String = event
+        .fields
+        .iter()
+        .map(|(k, v)| format!("  {}={}", k, v))
+        .collect();
+    format!(
+        "[{}] {}: {}{}",
+        event.source, event.kind, event.message, fields
+    )
+}
+
+pub fn start_capture() {
+    *CAPTURED.lock().unwrap() = Some(Captured {
+        lines: Vec::new(),
+        dropped: 0,
+    });
+}
+
+pub fn finish_capture() -> Vec<String> {
+    let Some(mut captured) = CAPTURED.lock().unwrap().take() else {
+        return Vec::new();
+    };
+    if captured.dropped > 0 {
+        captured
+            .lines
+            .push(format!("... {} more events not kept", captured.dropped));
+    }
+    captured.lines
+}
+
+pub fn emit(module_path: &str, kind: EventKind, message: &str, fields: &[(&str, String)]) {
+    // events go to stderr so they never mix with the answer on stdout
+    let event = Event {
+        source: source_name(module_path),
+        kind: kind.name(),
+        message,
+        fields: fields.iter().map(|(k, v)| (*k, v.as_str())).collect(),
+    };
+    let line = render(&event, JSON.load(Ordering::Relaxed));
+    match CAPTURED.lock().unwrap().as_mut() {
+        Some(captured) if captured.lines.len() < CAPTURE_LIMIT => captured.lines.push(line),
+        Some(captured) => captured.dropped += 1,
+        None => eprintln!("{}", line),
+    }
+}
+
+/// Emit a trace event when `--explain` is high enough, e.g.
+/// `explain!(State, "moved knots"; knots = knot_positions)`.
+/// Field values are rendered with their `Debug` impl.
+#[macro_export]
+macro_rules! explain {
+    ($kind:ident, $($message:expr),+ $(; $($key:ident = $value:expr),+)?) => {
+        if $crate::trace::enabled($crate::trace::EventKind::$kind) {
+            $crate::trace::emit(
+                module_path!(),
+                $crate::trace::EventKind::$kind,
+                &format!($($message),+),
+                &[$($((stringify!($key), format!("{:?}", $value))),+)?],
+            );
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event<'a>(fields: Vec<(&'a str, &'a str)>) -> Event<'a> {
+        Event {
+            source: "day_9",
+            kind: "state",
+            message: "moved knots",
+            fields,
+        }
+    }
+
+    #[test]
+    fn test_source_name() {
+        assert_eq!(source_name("advent_of_code_2022::days::day_9"), "day_9");
+    }
+
+    #[test]
+    fn test_render_text() {
+        let e = event(vec![("tail", "(1, 2)")]);
+        assert_eq!(render(&e, false), "[day_9] state: moved knots  tail=(1, 2)");
+    }
+
+    #[test]
+    fn test_render_json() {
+        let e = event(vec![("tail", "(1, 2)")]);
+        assert_eq!(
+            render(&e, true),
+            r#"{"source":"day_9","kind":"state","message":"moved knots","fields":{"tail":"(1, 2)"}}"#
+        );
+    }
+
+    #[test]
+    fn test_capture() {
+        start_capture();
+        emit("day_7", EventKind::Step, "cd into /", &[]);
+        assert_eq!(finish_capture(), vec!["[day_7] step: cd into /"]);
+        assert_eq!(finish_capture(), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,99 @@
+use advent_of_code_2022::days;
+use advent_of_code_2022::{read_test_file, DEFAULT_YEAR};
+use std::env;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// builds tests/ffi/harness.c against the cdylib with the system cc, then
+// checks it gets the same answers through the C interface as the solvers do
+
+const LIBRARY: &str = "libadvent_of_code_2022.so";
+
+fn library_dir() -> PathBuf {
+    // test binaries live in target/<profile>/deps, next to the cdylib
+    let deps = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    [deps.clone(), deps.parent().unwrap().to_path_buf()]
+        .into_iter()
+        .find(|dir| dir.join(LIBRARY).exists())
+        .unwrap_or_else(|| panic!("could not find {} near {}", LIBRARY, deps.display()))
+}
+
+fn build_harness(root: &Path, library_dir: &Path) -> PathBuf {
+    let harness = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ffi_harness");
+    let status = Command::new("cc")
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-I"])
+        .arg(root.join("include"))
+        .arg(root.join("tests/ffi/harness.c"))
+        .arg("-L")
+        .arg(library_dir)
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .args(["-ladvent_of_code_2022", "-o"])
+        .arg(&harness)
+        .status()
+        .expect("could not run cc");
+    assert!(status.success(), "cc failed to build the harness");
+    harness
+}
+
+fn expected_line(solution: &days::Solution, part: char) -> String {
+    let input = read_test_file(solution.day);
+    match panic::catch_unwind(|| {
+        solution
+            .solve(part, &input, days::Options::default())
+            .unwrap()
+    }) {
+        Ok(Ok(answer)) => format!(
+            "{} {} {}",
+            solution.day,
+            part,
+            answer.to_string().replace('\n', "\\n")
+        ),
+        Ok(Err(error)) => error_line(solution.day, part, &error.to_string()),
+        Err(payload) => error_line(solution.day, part, &days::panic_message(payload.as_ref())),
+    }
+}
+
+fn error_line(day: u32, part: char, message: &str) -> String {
+    format!("{} {} error {}", day, part, message.replace('\n', "\\n"))
+}
+
+fn without_kind(line: &str) -> String {
+    // `<day> <part> <kind> <text>` becomes `<day> <part> <text>`, with errors
+    // marked as such since their text is the solver's message
+    let fields: Vec<&str> = line.splitn(4, ' ').collect();
+    match fields[2] {
+        "0" => format!("{} {} error {}", fields[0], fields[1], fields[3]),
+        _ => format!("{} {} {}", fields[0], fields[1], fields[3]),
+    }
+}
+
+#[test]
+fn test_c_harness_matches_solvers() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let harness = build_harness(&root, &library_dir());
+
+    let solutions: Vec<days::Solution> = days::registry()
+        .into_iter()
+        .filter(|s| s.year == DEFAULT_YEAR)
+        .collect();
+    let last_day = solutions.iter().map(|s| s.day).max().unwrap();
+    let output = Command::new(harness)
+        .arg(root.join("inputs/test"))
+        .arg(last_day.to_string())
+        .current_dir(&root)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let lines: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(without_kind)
+        .collect();
+    let expected: Vec<String> = solutions
+        .iter()
+        .flat_map(|s| ['a', 'b'].map(|p| expected_line(s, p)))
+        .collect();
+    assert_eq!(lines, expected);
+}
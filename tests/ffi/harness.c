@@ -0,0 +1,72 @@
+/*
+ * Solves both parts of every day through the C interface and prints one line
+ * per part: `<day> <part> <kind> <text>`, with newlines in the text escaped.
+ * Usage: harness <inputs dir> <last day>
+ */
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "advent_of_code_2022.h"
+
+static char *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (file == NULL) {
+        return NULL;
+    }
+    fseek(file, 0, SEEK_END);
+    *len = (size_t)ftell(file);
+    fseek(file, 0, SEEK_SET);
+    char *data = malloc(*len);
+    if (data != NULL && fread(data, 1, *len, file) != *len) {
+        free(data);
+        data = NULL;
+    }
+    fclose(file);
+    return data;
+}
+
+static void print_escaped(const char *text) {
+    for (const char *c = text; *c != '\0'; c++) {
+        if (*c == '\n') {
+            fputs("\\n", stdout);
+        } else {
+            putchar(*c);
+        }
+    }
+}
+
+int main(int argc, char **argv) {
+    if (argc != 3) {
+        fprintf(stderr, "usage: %s <inputs dir> <last day>\n", argv[0]);
+        return 2;
+    }
+    int last_day = atoi(argv[2]);
+    for (int day = 1; day <= last_day; day++) {
+        char path[4096];
+        snprintf(path, sizeof(path), "%s/day_%d.txt", argv[1], day);
+        size_t len = 0;
+        char *input = read_file(path, &len);
+        if (input == NULL) {
+            fprintf(stderr, "could not read %s\n", path);
+            return 1;
+        }
+        for (char part = 'a'; part <= 'b'; part++) {
+            aoc_result *result = aoc_solve((uint32_t)day, part, input, len);
+            printf("%d %c %d ", day, part, (int)result->kind);
+            switch (result->kind) {
+            case AOC_KIND_UNSIGNED:
+                printf("%llu", (unsigned long long)result->unsigned_value);
+                break;
+            case AOC_KIND_SIGNED:
+                printf("%lld", (long long)result->signed_value);
+                break;
+            default:
+                print_escaped(result->text);
+            }
+            putchar('\n');
+            aoc_result_free(result);
+        }
+        free(input);
+    }
+    return 0;
+}
@@ -0,0 +1,44 @@
+use std::process::Command;
+
+// plaintext puzzle inputs must never be committed, wherever they sit under
+// `inputs/`, while the test set and encrypted copies are
+
+fn ignored(path: &str) -> bool {
+    let status = Command::new("git")
+        .args(["check-ignore", "--quiet", "--no-index", path])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .expect("could not run git check-ignore");
+    // 0 when ignored, 1 when not, anything else is git failing
+    match status.code() {
+        Some(0) => true,
+        Some(1) => false,
+        _ => panic!("git check-ignore failed on {}: {}", path, status),
+    }
+}
+
+#[test]
+fn test_inputs_are_ignored() {
+    for path in [
+        "inputs/day_1.txt",
+        "inputs/alice/day_1.txt",
+        "inputs/2022/day_1.txt",
+        "inputs/2022/alice/day_1.txt",
+        "inputs/2023/bob/day_25.txt",
+    ] {
+        assert!(ignored(path), "{} should be ignored", path);
+    }
+}
+
+#[test]
+fn test_test_set_and_encrypted_inputs_are_kept() {
+    for path in [
+        "inputs/test/day_1.txt",
+        "inputs/2022/test/day_1.txt",
+        "inputs/2023/test/day_25.txt",
+        "inputs/2022/alice/day_1.txt.enc",
+        "inputs/2022/alice/answers.txt",
+    ] {
+        assert!(!ignored(path), "{} should not be ignored", path);
+    }
+}